@@ -0,0 +1,286 @@
+use std::{ffi::OsString, fmt, path::PathBuf};
+
+use neovideo_vlc::media_player::MAX_VOLUME;
+
+pub const USAGE: &str = "\
+Usage: neovideo [OPTIONS] [MEDIA]...
+
+Plays each MEDIA in turn, a MEDIA is a local path or an MRL such as https://...
+M3U, PLS and XSPF playlist files are expanded into their items.
+
+Options:
+    --start-time <TIME>    start the first media at TIME, in seconds or [h:]m:s
+    --volume <PERCENT>     initial volume, 0 to 200
+    --loop                 start over after the last media
+    --fullscreen           open the video window fullscreen
+    --no-ui                do not open the control window
+    --audio-track <ID>     select the audio track with the given id
+    --sub-file <PATH>      load an external subtitle file for the first media
+    --audio-sink <SINK>    send the decoded audio to SINK instead of the sound card:
+                           null discards it, meter only shows its levels and
+                           wav:<PATH> writes it to a WAV file
+    --vlc-arg <ARG>        pass ARG to libvlc, may be repeated
+    --test-pattern         show colour bars instead of playing media
+    -h, --help             print this help";
+
+#[derive(Debug, Default)]
+pub struct Options {
+    pub media: Vec<PathBuf>,
+    /// Seconds into the first media.
+    pub start_time: Option<f64>,
+    pub volume: Option<i32>,
+    pub repeat: bool,
+    pub fullscreen: bool,
+    pub no_ui: bool,
+    pub audio_track: Option<i32>,
+    pub sub_file: Option<PathBuf>,
+    pub audio_sink: Option<AudioSinkOption>,
+    pub vlc_args: Vec<String>,
+    pub test_pattern: bool,
+}
+
+/// Where `--audio-sink` sends the decoded audio.
+#[derive(Debug, PartialEq)]
+pub enum AudioSinkOption {
+    Null,
+    Meter,
+    Wav(PathBuf),
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Help,
+    Invalid(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Help => f.write_str(USAGE),
+            CliError::Invalid(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+        }
+    }
+}
+
+impl Options {
+    pub fn parse<I>(args: I) -> Result<Options, CliError>
+    where
+        I: IntoIterator<Item = OsString>,
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        let mut only_media = false;
+        while let Some(arg) = args.next() {
+            let flag = match arg.to_str() {
+                Some(s) if !only_media && s.starts_with('-') && s != "-" => s.to_owned(),
+                _ => {
+                    options.media.push(PathBuf::from(arg));
+                    continue;
+                }
+            };
+            // both `--flag value` and `--flag=value` are accepted
+            let (name, inline) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_owned(), Some(OsString::from(value))),
+                None => (flag.clone(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| CliError::Invalid(format!("{} requires a value", name)))
+            };
+            match name.as_str() {
+                "--" => only_media = true,
+                "-h" | "--help" => return Err(CliError::Help),
+                "--loop" => options.repeat = true,
+                "--fullscreen" => options.fullscreen = true,
+                "--no-ui" => options.no_ui = true,
+                "--test-pattern" => options.test_pattern = true,
+                "--start-time" => {
+                    let v = value()?;
+                    options.start_time = Some(parse_time(&v).ok_or_else(|| invalid(&name, &v))?);
+                }
+                "--volume" => {
+                    let v = value()?;
+                    options.volume = Some(
+                        v.to_str()
+                            .and_then(|s| s.parse().ok())
+                            .filter(|v| (0..=MAX_VOLUME).contains(v))
+                            .ok_or_else(|| invalid(&name, &v))?,
+                    );
+                }
+                "--audio-track" => {
+                    let v = value()?;
+                    options.audio_track = Some(
+                        v.to_str()
+                            .and_then(|s| s.parse().ok())
+                            .ok_or_else(|| invalid(&name, &v))?,
+                    );
+                }
+                "--sub-file" => options.sub_file = Some(PathBuf::from(value()?)),
+                "--audio-sink" => {
+                    let v = value()?;
+                    options.audio_sink = Some(match v.to_str() {
+                        Some("null") => AudioSinkOption::Null,
+                        Some("meter") => AudioSinkOption::Meter,
+                        Some(s) if s.starts_with("wav:") && s.len() > 4 => {
+                            AudioSinkOption::Wav(PathBuf::from(&s[4..]))
+                        }
+                        _ => return Err(invalid(&name, &v)),
+                    });
+                }
+                "--vlc-arg" => {
+                    let v = value()?;
+                    options
+                        .vlc_args
+                        .push(v.to_str().ok_or_else(|| invalid(&name, &v))?.to_owned());
+                }
+                _ => return Err(CliError::Invalid(format!("unknown option {}", name))),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Input options for the first media, see `--start-time` and `--sub-file`.
+    pub fn first_media_options(&self) -> Vec<String> {
+        let mut media_options = Vec::new();
+        if let Some(start_time) = self.start_time {
+            media_options.push(format!(":start-time={}", start_time));
+        }
+        if let Some(sub_file) = &self.sub_file {
+            media_options.push(format!(":sub-file={}", sub_file.display()));
+        }
+        media_options
+    }
+
+    /// Input options for every media.
+    pub fn media_options(&self) -> Vec<String> {
+        self.audio_track
+            .map(|track| format!(":audio-track={}", track))
+            .into_iter()
+            .collect()
+    }
+}
+
+fn invalid(name: &str, value: &OsString) -> CliError {
+    CliError::Invalid(format!(
+        "invalid value '{}' for {}",
+        value.to_string_lossy(),
+        name
+    ))
+}
+
+/// Parses `90`, `12.5`, `1:30` or `1:02:03` into seconds.
+fn parse_time(value: &OsString) -> Option<f64> {
+    let mut seconds = 0.0;
+    let parts: Vec<&str> = value.to_str()?.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    for part in parts {
+        let v: f64 = part.parse().ok()?;
+        if v < 0.0 || !v.is_finite() {
+            return None;
+        }
+        seconds = seconds * 60.0 + v;
+    }
+    Some(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, CliError> {
+        Options::parse(args.iter().map(OsString::from))
+    }
+
+    fn start_time(value: &str) -> Option<f64> {
+        parse(&["--start-time", value]).ok()?.start_time
+    }
+
+    #[test]
+    fn start_time_takes_seconds_or_clock_time() {
+        assert_eq!(start_time("90"), Some(90.0));
+        assert_eq!(start_time("12.5"), Some(12.5));
+        assert_eq!(start_time("1:30"), Some(90.0));
+        assert_eq!(start_time("1:02:03"), Some(3723.0));
+        assert_eq!(start_time("0:0:0.5"), Some(0.5));
+        assert_eq!(
+            parse(&["--start-time=1:30"]).unwrap().start_time,
+            Some(90.0)
+        );
+        for bad in ["", "-5", "1::2", "1:2:3:4", "ten", "inf", "NaN"] {
+            assert!(start_time(bad).is_none(), "{:?}", bad);
+        }
+        assert!(matches!(
+            parse(&["--start-time"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert_eq!(
+            parse(&["--start-time", "5"]).unwrap().first_media_options(),
+            [":start-time=5"]
+        );
+    }
+
+    #[test]
+    fn vlc_args_accumulate_in_order() {
+        let options = parse(&[
+            "--vlc-arg",
+            "--no-audio",
+            "movie.mkv",
+            "--vlc-arg=--verbose=2",
+            "--vlc-arg",
+            "-q",
+        ])
+        .unwrap();
+        assert_eq!(options.vlc_args, ["--no-audio", "--verbose=2", "-q"]);
+        assert_eq!(options.media, [PathBuf::from("movie.mkv")]);
+    }
+
+    #[test]
+    fn media_keeps_paths_and_mrls_in_order() {
+        let options = parse(&[
+            "clip.mp4",
+            "https://example.com/live.m3u8",
+            "--loop",
+            "-",
+            "dir/list.xspf",
+            "--",
+            "--not-an-option.mkv",
+            "file:///tmp/a%20b.mkv",
+        ])
+        .unwrap();
+        assert!(options.repeat);
+        assert_eq!(
+            options.media,
+            [
+                "clip.mp4",
+                "https://example.com/live.m3u8",
+                "-",
+                "dir/list.xspf",
+                "--not-an-option.mkv",
+                "file:///tmp/a%20b.mkv",
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_options_and_bad_values() {
+        assert!(matches!(parse(&["--nope"]), Err(CliError::Invalid(_))));
+        assert!(matches!(
+            parse(&["--volume", "201"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert!(matches!(
+            parse(&["--audio-sink", "wav:"]),
+            Err(CliError::Invalid(_))
+        ));
+        assert!(matches!(parse(&["-h", "--nope"]), Err(CliError::Help)));
+        assert_eq!(
+            parse(&["--audio-sink", "wav:out.wav"]).unwrap().audio_sink,
+            Some(AudioSinkOption::Wav(PathBuf::from("out.wav")))
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 pub enum AppState {
     Running,
     Exit,
@@ -5,4 +7,23 @@ pub enum AppState {
 
 pub trait EguiApp {
     fn update(&mut self, ctx: &egui::Context, control_flow: &mut AppState);
+
+    /// Called before `update` on every redraw, native textures shown by the app are
+    /// registered with the painter here.
+    fn update_textures(&mut self, _gl: &glow::Context, _painter: &mut egui_glow::Painter) {}
+
+    /// How long the window may go without a redraw when no event arrives, `None` waits
+    /// for the next event however long that takes.
+    fn repaint_after(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The window title after `update`, `None` leaves it as it is.
+    fn window_title(&self) -> Option<String> {
+        None
+    }
+
+    /// Called once when the event loop ends. The app itself is never dropped, so
+    /// anything that must be finished, such as files being written, is done here.
+    fn exit(&mut self) {}
 }
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use glutin::{ContextError, ContextWrapper, PossiblyCurrent};
 use winit::window::{Window, WindowBuilder};
 
@@ -8,14 +10,24 @@ pub struct WinitEguiEventListener {
     gl: std::sync::Arc<glow::Context>,
     app: Box<dyn EguiApp>,
     gl_context: Option<glutin::WindowedContext<PossiblyCurrent>>,
+    /// Last title set from the app.
+    title: Option<String>,
 }
 
 impl WinitEguiEventListener {
-    pub fn new(
-        event_loop: &glutin::event_loop::EventLoop<()>,
+    /// Opens the window, `create_app` is called once its GL context is current so that
+    /// the app can share GL objects with it.
+    pub fn new<T, F>(
+        event_loop: &glutin::event_loop::EventLoop<T>,
         window_builder: WindowBuilder,
-        app: Box<dyn EguiApp>,
-    ) -> Self {
+        create_app: F,
+    ) -> Self
+    where
+        F: FnOnce(
+            &glutin::event_loop::EventLoop<T>,
+            &glutin::WindowedContext<PossiblyCurrent>,
+        ) -> Box<dyn EguiApp>,
+    {
         let gl_window = unsafe {
             glutin::ContextBuilder::new()
                 .with_depth_buffer(0)
@@ -31,12 +43,19 @@ impl WinitEguiEventListener {
         let gl = unsafe { glow::Context::from_loader_function(|s| gl_window.get_proc_address(s)) };
         let gl = std::sync::Arc::new(gl);
         let egui_glow = egui_glow::EguiGlow::new(event_loop, gl.clone());
-        Self {
+        let app = create_app(event_loop, &gl_window);
+        let mut listener = Self {
             egui_glow,
             gl,
             app,
             gl_context: Some(gl_window),
+            title: None,
+        };
+        // the app may have switched to a context of its own
+        unsafe {
+            listener.make_current();
         }
+        listener
     }
 
     #[inline]
@@ -64,10 +83,18 @@ impl WinitEguiEventListener {
         unsafe {
             self.make_current();
         }
+        self.app
+            .update_textures(&self.gl, &mut self.egui_glow.painter);
         let window = self.gl_context.as_ref().unwrap().window();
         let needs_repaint = self.egui_glow.run(window, |egui_ctx| {
             self.app.update(egui_ctx, &mut app_state);
         });
+        if let Some(title) = self.app.window_title() {
+            if self.title.as_ref() != Some(&title) {
+                window.set_title(&title);
+                self.title = Some(title);
+            }
+        }
 
         {
             unsafe {
@@ -85,15 +112,20 @@ impl WinitEguiEventListener {
             AppState::Running => {
                 if needs_repaint {
                     window.request_redraw();
-                    glutin::event_loop::ControlFlow::Poll
-                } else {
-                    glutin::event_loop::ControlFlow::Wait
+                }
+                // anything else wakes the loop with an event of its own
+                match self.app.repaint_after() {
+                    Some(after) if !needs_repaint => {
+                        glutin::event_loop::ControlFlow::WaitUntil(Instant::now() + after)
+                    }
+                    _ => glutin::event_loop::ControlFlow::Wait,
                 }
             }
         }
     }
 
     pub fn process_destroy(&mut self) {
+        self.app.exit();
         self.egui_glow.destroy();
     }
 
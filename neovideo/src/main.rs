@@ -1,169 +1,151 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::path::{Path, PathBuf};
-
-use egui_app::{
-    egui_app::{AppState, EguiApp},
-    winit_egui_event_listener::WinitEguiEventListener,
+use cli::{AudioSinkOption, CliError, Options};
+use egui_app::winit_egui_event_listener::WinitEguiEventListener;
+use glutin::{
+    event::{Event, StartCause},
+    event_loop::ControlFlow,
+    window::Fullscreen,
+};
+use neovideo_app::NeovideoApp;
+use neovideo_vlc::{
+    AudioLevels, AudioSink, LevelMeter, NeovideoError, NullSink, PlaylistFormat, Repeat,
+    TestPatternSource, WavWriter,
 };
-use glutin::{event_loop::ControlFlow, ContextWrapper, PossiblyCurrent};
-use neovideo_vlc::vlcvideo::{TextureRender, VLCVideo};
-use winit::window::{Window, WindowBuilder, WindowId};
+use player::Player;
 
+mod cli;
 mod egui_app;
-
-struct NeovideoApp {}
-
-impl EguiApp for NeovideoApp {
-    fn update(&mut self, ctx: &egui::Context, app_state: &mut AppState) {
-        egui::SidePanel::left("my_side_panel").show(ctx, |ui| {
-            ui.heading("Hello World!");
-            if ui.button("Quit").clicked() {
-                *app_state = AppState::Exit;
-            }
-            ui.color_edit_button_rgb(&mut [0.1, 0.1, 0.1]);
-        });
-    }
-}
-
-impl NeovideoApp {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-struct PlayerWindow {
-    texture_render: TextureRender,
-    video_decoder: VLCVideo,
-    window_context: Option<glutin::WindowedContext<PossiblyCurrent>>,
+mod neovideo_app;
+mod player;
+mod settings;
+
+/// Sent from decoding and libvlc threads to wake the event loop.
+#[derive(Clone, Copy, Debug)]
+pub enum UserEvent {
+    /// A new frame or a player event is waiting, the window needs a redraw.
+    Redraw,
 }
 
-impl PlayerWindow {
-    #[allow(unused)]
-    pub fn new(
-        event_loop: &glutin::event_loop::EventLoop<()>,
-        window_builder: WindowBuilder,
-    ) -> Self {
-        let window_context = unsafe {
-            glutin::ContextBuilder::new()
-                .with_depth_buffer(0)
-                .with_srgb(true)
-                .with_stencil_buffer(0)
-                .build_windowed(window_builder, event_loop)
-                .unwrap()
-                .make_current()
-                .unwrap()
-        };
-        let texture_render = TextureRender::new(&window_context);
-        let video_decoder = VLCVideo::new(window_context.context(), event_loop);
-        PlayerWindow {
-            texture_render,
-            window_context: Some(window_context),
-            video_decoder,
-        }
-    }
-
-    pub fn window(&self) -> &Window {
-        self.window_context.as_ref().unwrap().window()
-    }
-
-    pub fn play<T>(&mut self, path: T)
-    where
-        T: AsRef<Path>,
-    {
-        self.video_decoder.play_media(path).unwrap();
-    }
-
-    #[allow(unused)]
-    pub unsafe fn make_current(&mut self) {
-        self.window_context.take().map(|ctx| {
-            self.window_context = Some(
-                ctx.make_current()
-                    .expect("make_current error in PlayerWindow::make_current"),
-            );
-        });
-    }
-
-    #[allow(unused)]
-    pub fn render_frame(&mut self) {
-        unsafe {
-            self.make_current();
+fn main() {
+    let options = match Options::parse(std::env::args_os().skip(1)) {
+        Ok(options) => options,
+        Err(CliError::Help) => {
+            println!("{}", CliError::Help);
+            return;
         }
-        let mut update: bool = false;
-        let tex = self.video_decoder.get_video_frame(&mut update);
-        if update {
-            println!("render_frame: {}", tex);
-            self.texture_render.draw_video_frame(tex);
+        Err(e) => {
+            eprintln!("neovideo: {}", e);
+            std::process::exit(2);
         }
-    }
-}
+    };
 
-fn main() {
-    let event_loop = glutin::event_loop::EventLoop::with_user_event();
-    let app = Box::new(NeovideoApp::new());
+    let event_loop = glutin::event_loop::EventLoop::<UserEvent>::with_user_event();
 
     let window_builder = glutin::window::WindowBuilder::new()
         .with_resizable(true)
         .with_decorations(true)
-        .with_title("Egui Demo");
-
-    let mut egui_listener = WinitEguiEventListener::new(&event_loop, window_builder, app);
-    let egui_winid = egui_listener.window().id();
-
-    let window_builder = glutin::window::WindowBuilder::new()
         .with_title("Neovideo")
-        .with_resizable(true);
-
-    let mut player_window = PlayerWindow::new(&event_loop, window_builder);
-
-    let player_winid = player_window.window().id();
-    // player_window.play("file:///~/Movies/testmp4.mp4");
-    player_window.play(
-        "http://commondatastorage.googleapis.com/gtv-videos-bucket/sample/ElephantsDream.mp4",
-    );
-
-    event_loop.run(move |event, _, control_flow| {
-        player_window.render_frame();
-        player_window
-            .window_context
-            .as_ref()
-            .unwrap()
-            .swap_buffers()
-            .unwrap();
-        player_window.window().request_redraw();
-        match event {
-            glutin::event::Event::RedrawEventsCleared if cfg!(windows) => {
-                *control_flow = egui_listener.process_redraw();
-                egui_listener.swap_buffers();
+        .with_fullscreen(options.fullscreen.then(|| Fullscreen::Borderless(None)));
+
+    let mut egui_listener =
+        WinitEguiEventListener::new(&event_loop, window_builder, |event_loop, gl_window| {
+            if options.test_pattern {
+                let source = TestPatternSource::new(1280, 720, 25.0);
+                let mut player = Player::with_source(Box::new(source));
+                player.set_event_proxy(event_loop.create_proxy());
+                return Box::new(NeovideoApp::new(player, !options.no_ui));
+            }
+            let mut player = match Player::new(event_loop, gl_window, &options.vlc_args) {
+                Ok(player) => player,
+                Err(e) => {
+                    eprintln!("neovideo: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            player.set_event_proxy(event_loop.create_proxy());
+            if let Some(playlist) = player.playlist_mut() {
+                for media in &options.media {
+                    let result = if PlaylistFormat::from_path(media).is_some() {
+                        playlist.import(media).map(|_| ())
+                    } else {
+                        playlist.add_url(media).map(|_| ())
+                    };
+                    if let Err(e) = result {
+                        eprintln!("neovideo: {}", e);
+                    }
+                }
+                playlist.set_repeat(if options.repeat {
+                    Repeat::All
+                } else {
+                    Repeat::Off
+                });
             }
-            glutin::event::Event::RedrawRequested(window_id) if !cfg!(windows) => {
-                if window_id == egui_winid {
-                    *control_flow = egui_listener.process_redraw();
-                    egui_listener.swap_buffers();
-                } else if window_id == player_winid {
-                    println!("redraw request");
+            if let Some(option) = &options.audio_sink {
+                let result = audio_sink(option)
+                    .and_then(|(sink, levels)| player.set_audio_sink(sink, levels));
+                if let Err(e) = result {
+                    eprintln!("neovideo: {}", e);
+                    std::process::exit(1);
                 }
             }
+            player.set_media_options(options.media_options());
+            player.set_start_volume(options.volume);
+            if let Err(e) = player.play_next(&options.first_media_options()) {
+                eprintln!("neovideo: {}", e);
+                gl_window.window().set_title(&format!("Neovideo - {}", e));
+            }
+            Box::new(NeovideoApp::new(player, !options.no_ui))
+        });
 
-            glutin::event::Event::WindowEvent { event, .. } => {
-                use glutin::event::WindowEvent;
-                if matches!(event, WindowEvent::CloseRequested | WindowEvent::Destroyed) {
-                    *control_flow = ControlFlow::Exit;
-                }
+    event_loop.run(move |event, _, control_flow| match event {
+        Event::RedrawEventsCleared if cfg!(windows) => {
+            *control_flow = egui_listener.process_redraw();
+            egui_listener.swap_buffers();
+        }
+        Event::RedrawRequested(_) if !cfg!(windows) => {
+            *control_flow = egui_listener.process_redraw();
+            egui_listener.swap_buffers();
+        }
 
-                egui_listener.process_event(&event);
+        Event::UserEvent(UserEvent::Redraw)
+        | Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+            egui_listener.window().request_redraw();
+        }
 
-                egui_listener.window().request_redraw();
-            }
-            glutin::event::Event::LoopDestroyed => {
-                egui_listener.process_destroy();
+        Event::WindowEvent { event, .. } => {
+            use glutin::event::WindowEvent;
+            if matches!(event, WindowEvent::CloseRequested | WindowEvent::Destroyed) {
+                *control_flow = ControlFlow::Exit;
             }
 
-            _ => (),
+            egui_listener.process_event(&event);
+
+            egui_listener.window().request_redraw();
         }
+        Event::LoopDestroyed => {
+            egui_listener.process_destroy();
+        }
+
+        _ => (),
     });
 }
 
+/// The sink for `--audio-sink` and, for the meter, its levels.
+fn audio_sink(
+    option: &AudioSinkOption,
+) -> Result<(Box<dyn AudioSink>, Option<AudioLevels>), NeovideoError> {
+    Ok(match option {
+        AudioSinkOption::Null => (Box::new(NullSink::new()), None),
+        AudioSinkOption::Meter => {
+            let meter = LevelMeter::new();
+            let levels = meter.levels();
+            (Box::new(meter), Some(levels))
+        }
+        AudioSinkOption::Wav(path) => (Box::new(WavWriter::create(path)?), None),
+    })
+}
+
 fn create_display(
     event_loop: &glutin::event_loop::EventLoop<()>,
 ) -> (
@@ -0,0 +1,146 @@
+use neovideo_vlc::{vlcvideo::VLCVideo, AudioDevice, AudioOutput};
+
+use crate::player::Player;
+use crate::settings::Settings;
+
+const OUTPUT_KEY: &str = "audio.output";
+const DEVICE_KEY: &str = "audio.device";
+
+/// Audio output module and device pickers, the choice is kept in the settings.
+#[derive(Default)]
+pub struct AudioDevicePanel {
+    /// Cached as enumerating devices can take a while, see `refresh`.
+    outputs: Vec<AudioOutput>,
+    devices: Vec<AudioDevice>,
+    error: Option<String>,
+}
+
+impl AudioDevicePanel {
+    /// Restores the saved output and device. If they are gone the defaults play and
+    /// the saved choice is kept for when they come back.
+    pub fn new(settings: &Settings, player: &mut Player) -> Self {
+        let mut panel = AudioDevicePanel::default();
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return panel,
+        };
+        let output = settings.get(OUTPUT_KEY);
+        if let Err(e) = vlc.set_audio_output(output) {
+            panel.error = Some(format!("{}, using the default output", e));
+        } else if let Err(e) = vlc.set_audio_device(settings.get(DEVICE_KEY)) {
+            panel.error = Some(e.to_string());
+        }
+        panel.refresh(vlc);
+        panel
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player, settings: &mut Settings) {
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+
+        let output = vlc.audio_output().map(str::to_owned);
+        let output_name = match &output {
+            Some(name) => self
+                .outputs
+                .iter()
+                .find(|o| o.name == *name)
+                .map_or(name.as_str(), |o| o.description.as_str()),
+            None => "Default",
+        };
+        let mut new_output = None;
+        egui::ComboBox::from_label("Output")
+            .selected_text(output_name)
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(output.is_none(), "Default").clicked() {
+                    new_output = Some(None);
+                }
+                for o in &self.outputs {
+                    let selected = output.as_deref() == Some(o.name.as_str());
+                    if ui.selectable_label(selected, &o.description).clicked() {
+                        new_output = Some(Some(o.name.clone()));
+                    }
+                }
+            });
+        if let Some(new_output) = new_output {
+            match vlc.set_audio_output(new_output.as_deref()) {
+                Ok(()) => {
+                    self.error = None;
+                    set_or_remove(settings, OUTPUT_KEY, new_output);
+                    settings.remove(DEVICE_KEY);
+                    self.save(settings);
+                    self.refresh(vlc);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+
+        let device = vlc.audio_device().map(str::to_owned);
+        let device_name = match &device {
+            Some(id) => match self.devices.iter().find(|d| d.id == *id) {
+                Some(d) => d.description.clone(),
+                // nothing is listed for the default output before the first media
+                None if self.devices.is_empty() => id.clone(),
+                None => format!("{} (unavailable, using the default)", id),
+            },
+            None => "Default".to_owned(),
+        };
+        let mut new_device = None;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Device")
+                .selected_text(device_name)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(device.is_none(), "Default").clicked() {
+                        new_device = Some(None);
+                    }
+                    for d in &self.devices {
+                        let selected = device.as_deref() == Some(d.id.as_str());
+                        if ui.selectable_label(selected, &d.description).clicked() {
+                            new_device = Some(Some(d.id.clone()));
+                        }
+                    }
+                });
+            if ui.button("Refresh").clicked() {
+                self.refresh(vlc);
+            }
+        });
+        if let Some(new_device) = new_device {
+            match vlc.set_audio_device(new_device.as_deref()) {
+                Ok(()) => {
+                    self.error = None;
+                    set_or_remove(settings, DEVICE_KEY, new_device);
+                    self.save(settings);
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+        if output.is_some() {
+            ui.small("Output changes apply from the next media on");
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    /// Lists the outputs and the devices of the chosen output again, after a device
+    /// was plugged in or removed.
+    fn refresh(&mut self, vlc: &VLCVideo) {
+        self.outputs = vlc.audio_outputs();
+        self.devices = vlc.audio_devices();
+    }
+
+    fn save(&mut self, settings: &Settings) {
+        if let Err(e) = settings.save() {
+            self.error = Some(e.to_string());
+        }
+    }
+}
+
+fn set_or_remove(settings: &mut Settings, key: &str, value: Option<String>) {
+    match value {
+        Some(value) => settings.set(key, value),
+        None => settings.remove(key),
+    }
+}
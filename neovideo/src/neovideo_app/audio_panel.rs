@@ -0,0 +1,106 @@
+use neovideo_vlc::{vlc::AudioChannel, ChannelLevel};
+
+use crate::player::Player;
+
+/// Milliseconds one press of the delay buttons or the J and K keys moves the audio by.
+const DELAY_STEP: i64 = 50;
+
+#[derive(Default)]
+pub struct AudioPanel {
+    error: Option<String>,
+}
+
+impl AudioPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        if let Some(levels) = player.audio_levels() {
+            level_meter(ui, &levels.get());
+        }
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+
+        let delay = vlc.audio_delay();
+        let mut new_delay = None;
+        ui.horizontal(|ui| {
+            ui.label(format!("Delay {:+.3} s", delay as f64 / 1000.0));
+            if ui.button(format!("-{} ms", DELAY_STEP)).clicked() {
+                new_delay = Some(delay - DELAY_STEP);
+            }
+            if ui
+                .add_enabled(delay != 0, egui::Button::new("Reset"))
+                .clicked()
+            {
+                new_delay = Some(0);
+            }
+            if ui.button(format!("+{} ms", DELAY_STEP)).clicked() {
+                new_delay = Some(delay + DELAY_STEP);
+            }
+        });
+        if let Some(new_delay) = new_delay {
+            self.error = vlc.set_audio_delay(new_delay).err().map(|e| e.to_string());
+        }
+
+        let channel = vlc.channel();
+        ui.add_enabled_ui(channel.is_some(), |ui| {
+            egui::ComboBox::from_label("Channels")
+                .selected_text(channel.map_or("-", |c| c.name()))
+                .show_ui(ui, |ui| {
+                    for c in AudioChannel::ALL {
+                        if ui.selectable_label(channel == Some(c), c.name()).clicked() {
+                            self.error = vlc.set_channel(c).err().map(|e| e.to_string());
+                        }
+                    }
+                });
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    /// Toggles mute with M and moves the audio with J and K as in VLC, unless a text
+    /// field has the focus.
+    pub fn handle_keys(&mut self, ctx: &egui::Context, player: &mut Player) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (mute, step) = {
+            let input = ctx.input();
+            let step = if input.key_pressed(egui::Key::J) {
+                -DELAY_STEP
+            } else if input.key_pressed(egui::Key::K) {
+                DELAY_STEP
+            } else {
+                0
+            };
+            (input.key_pressed(egui::Key::M), step)
+        };
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+        if mute {
+            vlc.toggle_mute();
+        }
+        if step != 0 {
+            let delay = vlc.audio_delay() + step;
+            self.error = vlc.set_audio_delay(delay).err().map(|e| e.to_string());
+        }
+    }
+}
+
+/// Peak bars from -60 to 0 dBFS for the audio sent to the level meter sink.
+fn level_meter(ui: &mut egui::Ui, levels: &[ChannelLevel]) {
+    if levels.is_empty() {
+        ui.label("No audio");
+    }
+    for (channel, level) in levels.iter().enumerate() {
+        let db = level.peak_db().max(-60.0);
+        ui.add(egui::ProgressBar::new((db + 60.0) / 60.0).text(format!(
+            "{} {:.1} dB",
+            channel + 1,
+            db
+        )));
+    }
+}
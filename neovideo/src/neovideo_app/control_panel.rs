@@ -0,0 +1,122 @@
+use neovideo_vlc::{media_player::MAX_VOLUME, vlc::State};
+
+use crate::player::Player;
+
+const RATES: [f32; 7] = [0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 2.0];
+
+#[derive(Default)]
+pub struct ControlPanel {
+    open_path: String,
+    error: Option<String>,
+    /// Slider position while the user drags it, the seek happens on release.
+    seek_preview: Option<f32>,
+}
+
+impl ControlPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.open_path);
+            if ui.button("Open").clicked() {
+                self.open(player);
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        ui.separator();
+
+        let video = player.source_mut();
+        let playing = video.is_playing();
+        let label = if playing { "Pause" } else { "Play" };
+        if ui
+            .add_enabled(!playing || video.can_pause(), egui::Button::new(label))
+            .clicked()
+        {
+            if video.state() != State::NothingSpecial {
+                video.toggle_pause();
+            } else {
+                self.open(player);
+            }
+        }
+
+        let video = player.source_mut();
+        let mut position = self
+            .seek_preview
+            .unwrap_or_else(|| video.position().unwrap_or(0.0));
+        let response = ui.add_enabled(
+            video.is_seekable(),
+            egui::Slider::new(&mut position, 0.0..=1.0).show_value(false),
+        );
+        if response.dragged() {
+            self.seek_preview = Some(position);
+        } else if response.changed() || response.drag_released() {
+            video.seek_position(position);
+            self.seek_preview = None;
+        }
+        let time = match (self.seek_preview, video.length()) {
+            (Some(preview), Some(length)) => Some((preview as f64 * length as f64) as i64),
+            _ => video.time(),
+        };
+        ui.label(format!(
+            "{} / {}",
+            format_time(time),
+            format_time(video.length())
+        ));
+        ui.separator();
+
+        if let Some(vlc) = player.vlc() {
+            ui.horizontal(|ui| {
+                let mut muted = vlc.is_muted();
+                if ui.checkbox(&mut muted, "Mute").changed() {
+                    vlc.set_mute(muted);
+                }
+                let mut volume = vlc.volume().unwrap_or(100);
+                let slider = egui::Slider::new(&mut volume, 0..=MAX_VOLUME).text("Volume");
+                if ui.add_enabled(vlc.volume().is_some(), slider).changed() {
+                    if let Err(e) = vlc.set_volume(volume) {
+                        self.error = Some(e.to_string());
+                    }
+                }
+            });
+        }
+
+        let video = player.source_mut();
+        let rate = video.rate();
+        egui::ComboBox::from_label("Speed")
+            .selected_text(format!("{}x", rate))
+            .show_ui(ui, |ui| {
+                for r in RATES {
+                    if ui
+                        .selectable_label((rate - r).abs() < 0.01, format!("{}x", r))
+                        .clicked()
+                    {
+                        if let Err(e) = video.set_rate(r) {
+                            self.error = Some(e.to_string());
+                        }
+                    }
+                }
+            });
+    }
+
+    fn open(&mut self, player: &mut Player) {
+        let path = self.open_path.trim();
+        if path.is_empty() {
+            return;
+        }
+        self.error = player.play(path).err().map(|e| e.to_string());
+    }
+}
+
+pub(super) fn format_time(ms: Option<i64>) -> String {
+    match ms {
+        Some(ms) => {
+            let s = ms / 1000;
+            if s >= 3600 {
+                format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+            } else {
+                format!("{}:{:02}", s / 60, s % 60)
+            }
+        }
+        None => "--:--".to_owned(),
+    }
+}
@@ -0,0 +1,287 @@
+use neovideo_vlc::Equalizer;
+
+use crate::player::Player;
+use crate::settings::Settings;
+
+/// Settings keys, saved curves are stored under `CURVE_PREFIX` plus their name.
+const ENABLED_KEY: &str = "equalizer.enabled";
+const CURRENT_KEY: &str = "equalizer.current";
+const CURVE_PREFIX: &str = "equalizer.curve.";
+
+/// Range libvlc clamps the preamp and band amplifications to, in dB.
+const AMP_RANGE: std::ops::RangeInclusive<f32> = -20.0..=20.0;
+
+/// A preamp and band amplifications in dB.
+#[derive(Clone)]
+struct Curve {
+    preamp: f32,
+    amps: Vec<f32>,
+}
+
+impl Curve {
+    fn flat() -> Curve {
+        Curve {
+            preamp: 0.0,
+            amps: vec![0.0; Equalizer::band_count() as usize],
+        }
+    }
+
+    fn from_equalizer(equalizer: &Equalizer) -> Curve {
+        Curve {
+            preamp: equalizer.preamp(),
+            amps: equalizer.amps(),
+        }
+    }
+
+    /// Parses the space separated preamp and amplifications written by `to_setting`,
+    /// `None` unless there is one amplification for each of `bands`.
+    fn from_setting(value: &str, bands: usize) -> Option<Curve> {
+        let mut values = value.split_whitespace().map(|v| v.parse::<f32>().ok());
+        let preamp = values.next()??;
+        let amps = values.collect::<Option<Vec<_>>>()?;
+        if amps.len() != bands {
+            return None;
+        }
+        Some(Curve { preamp, amps })
+    }
+
+    fn to_setting(&self) -> String {
+        let mut value = format!("{:.1}", self.preamp);
+        for amp in &self.amps {
+            value.push_str(&format!(" {:.1}", amp));
+        }
+        value
+    }
+}
+
+pub struct EqualizerPanel {
+    enabled: bool,
+    curve: Curve,
+    /// Name of the libvlc preset or saved curve last chosen, cleared by edits.
+    selected: Option<String>,
+    /// User curves by name, in the order they were saved.
+    curves: Vec<(String, Curve)>,
+    save_name: String,
+    error: Option<String>,
+}
+
+impl EqualizerPanel {
+    /// Restores the saved curves and the last equalizer state, applying it to `player`.
+    pub fn new(settings: &Settings, player: &mut Player) -> Self {
+        // curves saved with another libvlc version may have other bands, they are dropped
+        let bands = Equalizer::band_count() as usize;
+        let curves = settings
+            .with_prefix(CURVE_PREFIX)
+            .filter_map(|(name, value)| Some((name.to_owned(), Curve::from_setting(value, bands)?)))
+            .collect();
+        let mut panel = EqualizerPanel {
+            enabled: settings.get(ENABLED_KEY) == Some("true"),
+            curve: settings
+                .get(CURRENT_KEY)
+                .and_then(|value| Curve::from_setting(value, bands))
+                .unwrap_or_else(Curve::flat),
+            selected: None,
+            curves,
+            save_name: String::new(),
+            error: None,
+        };
+        panel.apply(player);
+        panel
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player, settings: &mut Settings) {
+        if player.vlc().is_none() {
+            return;
+        }
+        let mut changed = ui.checkbox(&mut self.enabled, "Enable").changed();
+        ui.add_enabled_ui(self.enabled, |ui| {
+            if let Some(curve) = self.preset_choices(ui) {
+                self.curve = curve;
+                changed = true;
+            }
+            let mut edited = false;
+            ui.horizontal(|ui| {
+                edited |= band_slider(ui, &mut self.curve.preamp, "Pre");
+                ui.separator();
+                for (band, amp) in self.curve.amps.iter_mut().enumerate() {
+                    let label = Equalizer::band_frequency(band as u32)
+                        .map_or_else(String::new, format_frequency);
+                    edited |= band_slider(ui, amp, &label);
+                }
+            });
+            if edited {
+                self.selected = None;
+                changed = true;
+            }
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.save_name);
+                let name = self.save_name.trim().to_owned();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new("Save curve"))
+                    .clicked()
+                {
+                    if name.contains('=') {
+                        self.error = Some("curve names cannot contain '='".to_owned());
+                    } else {
+                        self.save_curve(name, settings);
+                    }
+                }
+                let custom = self.selected_custom();
+                if ui
+                    .add_enabled(custom.is_some(), egui::Button::new("Delete"))
+                    .clicked()
+                {
+                    if let Some(index) = custom {
+                        let (name, _) = self.curves.remove(index);
+                        settings.remove(&format!("{}{}", CURVE_PREFIX, name));
+                        self.selected = None;
+                        self.save(settings);
+                    }
+                }
+            });
+        });
+
+        if changed {
+            self.apply(player);
+            // not on every step of a slider drag
+            if !ui.ctx().input().pointer.any_down() {
+                self.store(settings);
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    /// The preset dropdown, returns the curve picked.
+    fn preset_choices(&mut self, ui: &mut egui::Ui) -> Option<Curve> {
+        let mut picked = None;
+        egui::ComboBox::from_label("Preset")
+            .selected_text(self.selected.as_deref().unwrap_or("Custom"))
+            .show_ui(ui, |ui| {
+                for index in 0..Equalizer::preset_count() {
+                    let name = match Equalizer::preset_name(index) {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    let selected = self.selected.as_deref() == Some(name.as_str());
+                    if ui.selectable_label(selected, &name).clicked() {
+                        match Equalizer::from_preset(index) {
+                            Ok(equalizer) => {
+                                picked = Some(Curve::from_equalizer(&equalizer));
+                                self.selected = Some(name);
+                            }
+                            Err(e) => self.error = Some(e.to_string()),
+                        }
+                    }
+                }
+                if !self.curves.is_empty() {
+                    ui.separator();
+                }
+                for (name, curve) in &self.curves {
+                    let selected = self.selected.as_deref() == Some(name.as_str());
+                    if ui.selectable_label(selected, name).clicked() {
+                        picked = Some(curve.clone());
+                        self.selected = Some(name.clone());
+                    }
+                }
+            });
+        picked
+    }
+
+    /// Index of the chosen curve if it is a user curve.
+    fn selected_custom(&self) -> Option<usize> {
+        let selected = self.selected.as_deref()?;
+        self.curves.iter().position(|(name, _)| name == selected)
+    }
+
+    fn save_curve(&mut self, name: String, settings: &mut Settings) {
+        settings.set(
+            &format!("{}{}", CURVE_PREFIX, name),
+            self.curve.to_setting(),
+        );
+        match self.curves.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, curve)) => *curve = self.curve.clone(),
+            None => self.curves.push((name.clone(), self.curve.clone())),
+        }
+        self.selected = Some(name);
+        self.save_name.clear();
+        self.save(settings);
+    }
+
+    fn apply(&mut self, player: &mut Player) {
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+        let equalizer = if self.enabled {
+            match Equalizer::with_amps(self.curve.preamp, &self.curve.amps) {
+                Ok(equalizer) => Some(equalizer),
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        self.error = vlc.set_equalizer(equalizer).err().map(|e| e.to_string());
+    }
+
+    /// Remembers the state for the next start.
+    fn store(&mut self, settings: &mut Settings) {
+        settings.set(ENABLED_KEY, self.enabled.to_string());
+        settings.set(CURRENT_KEY, self.curve.to_setting());
+        self.save(settings);
+    }
+
+    fn save(&mut self, settings: &Settings) {
+        if let Err(e) = settings.save() {
+            self.error = Some(e.to_string());
+        }
+    }
+}
+
+/// A vertical slider with `label` below it, returns whether the value changed or a
+/// drag ended.
+fn band_slider(ui: &mut egui::Ui, value: &mut f32, label: &str) -> bool {
+    ui.vertical(|ui| {
+        let response = ui.add(
+            egui::Slider::new(value, AMP_RANGE)
+                .vertical()
+                .show_value(false),
+        );
+        let response = response.on_hover_text(format!("{:+.1} dB", value));
+        ui.small(label);
+        response.changed() || response.drag_released()
+    })
+    .inner
+}
+
+/// `60 Hz` as `60`, `14000 Hz` as `14K`.
+fn format_frequency(frequency: f32) -> String {
+    if frequency >= 1000.0 {
+        format!("{}K", (frequency / 100.0).round() / 10.0)
+    } else {
+        format!("{}", frequency.round())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_round_trip_and_need_every_band() {
+        let curve = Curve::from_setting("-1.5 0.0 2.0 3.5", 3).unwrap();
+        assert_eq!(curve.preamp, -1.5);
+        assert_eq!(curve.amps, [0.0, 2.0, 3.5]);
+        assert_eq!(curve.to_setting(), "-1.5 0.0 2.0 3.5");
+
+        assert!(Curve::from_setting("-1.5 0.0 2.0", 3).is_none());
+        assert!(Curve::from_setting("-1.5 0.0 2.0 3.5 4.0", 3).is_none());
+        assert!(Curve::from_setting("-1.5 0.0 loud 3.5", 3).is_none());
+        assert!(Curve::from_setting("", 0).is_none());
+    }
+}
@@ -0,0 +1,94 @@
+use neovideo_vlc::vlc::Meta;
+
+use crate::player::Player;
+
+use super::control_panel::format_time;
+
+#[derive(Default)]
+pub struct MediaInfoPanel {
+    /// Values being edited, in `Meta::ALL` order, `None` when not editing.
+    edits: Option<Vec<(Meta, String)>>,
+    error: Option<String>,
+}
+
+impl MediaInfoPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        let info = match player.media_info() {
+            Some(info) => info.clone(),
+            None => {
+                ui.label("No media");
+                return;
+            }
+        };
+        if let Some(title) = info.display_title() {
+            ui.strong(title);
+        }
+        if !info.parsed {
+            ui.label("Reading metadata...");
+        }
+
+        let mut save = None;
+        let mut cancel = false;
+        match &mut self.edits {
+            None => {
+                egui::Grid::new("media_info").num_columns(2).show(ui, |ui| {
+                    if let Some(duration) = info.duration {
+                        ui.label("Duration");
+                        ui.label(format_time(Some(duration)));
+                        ui.end_row();
+                    }
+                    for (meta, value) in info.iter() {
+                        ui.label(meta.name());
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                });
+                if ui.button("Edit").clicked() {
+                    self.edits = Some(
+                        Meta::ALL
+                            .into_iter()
+                            .map(|meta| (meta, info.get(meta).unwrap_or_default().to_owned()))
+                            .collect(),
+                    );
+                    self.error = None;
+                }
+            }
+            Some(edits) => {
+                egui::Grid::new("media_info_edit")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for (meta, value) in edits.iter_mut() {
+                            ui.label(meta.name());
+                            ui.text_edit_singleline(value);
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        // only what changed, so that untouched values stay as libvlc read them
+                        let changed: Vec<(Meta, String)> = edits
+                            .iter()
+                            .filter(|(meta, value)| info.get(*meta).unwrap_or_default() != value)
+                            .cloned()
+                            .collect();
+                        save = Some(changed);
+                    }
+                    cancel = ui.button("Cancel").clicked();
+                });
+            }
+        }
+
+        if cancel {
+            self.edits = None;
+        }
+        if let Some(changed) = save {
+            self.error = player.save_meta(&changed).err().map(|e| e.to_string());
+            if self.error.is_none() {
+                self.edits = None;
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use crate::egui_app::egui_app::{AppState, EguiApp};
+use crate::player::Player;
+use crate::settings::Settings;
+
+use audio_device_panel::AudioDevicePanel;
+use audio_panel::AudioPanel;
+use control_panel::ControlPanel;
+use equalizer_panel::EqualizerPanel;
+use media_info_panel::MediaInfoPanel;
+use playlist_panel::PlaylistPanel;
+use subtitle_overlay::SubtitleOverlay;
+use subtitle_panel::SubtitlePanel;
+use track_menu::TrackMenu;
+use video_view::VideoView;
+
+mod audio_device_panel;
+mod audio_panel;
+mod control_panel;
+mod equalizer_panel;
+mod media_info_panel;
+mod playlist_panel;
+mod subtitle_overlay;
+mod subtitle_panel;
+mod track_menu;
+mod video_view;
+
+/// Longest wait between redraws while playing, for the clock and the seek bar.
+const CLOCK_REFRESH: Duration = Duration::from_millis(250);
+/// Redraw interval while a level meter is shown.
+const METER_REFRESH: Duration = Duration::from_millis(50);
+
+pub struct NeovideoApp {
+    player: Player,
+    audio_device_panel: AudioDevicePanel,
+    audio_panel: AudioPanel,
+    control_panel: ControlPanel,
+    equalizer_panel: EqualizerPanel,
+    media_info_panel: MediaInfoPanel,
+    playlist_panel: PlaylistPanel,
+    subtitle_overlay: SubtitleOverlay,
+    subtitle_panel: SubtitlePanel,
+    track_menu: TrackMenu,
+    video_view: VideoView,
+    show_ui: bool,
+    settings: Settings,
+}
+
+impl EguiApp for NeovideoApp {
+    fn update(&mut self, ctx: &egui::Context, app_state: &mut AppState) {
+        self.player.poll_events();
+        self.audio_panel.handle_keys(ctx, &mut self.player);
+        self.subtitle_panel.handle_keys(ctx, &mut self.player);
+        if self.show_ui {
+            egui::SidePanel::left("my_side_panel").show(ctx, |ui| {
+                ui.heading("Neovideo");
+                self.control_panel.show(ui, &mut self.player);
+                ui.separator();
+                if self.player.vlc().is_some() {
+                    self.track_menu.show(ui, &mut self.player);
+                    egui::CollapsingHeader::new("Audio").show(ui, |ui| {
+                        self.audio_panel.show(ui, &mut self.player);
+                        ui.separator();
+                        self.audio_device_panel
+                            .show(ui, &mut self.player, &mut self.settings);
+                    });
+                    egui::CollapsingHeader::new("Equalizer").show(ui, |ui| {
+                        self.equalizer_panel
+                            .show(ui, &mut self.player, &mut self.settings);
+                    });
+                    egui::CollapsingHeader::new("Subtitles").show(ui, |ui| {
+                        self.subtitle_panel.show(ui, &mut self.player);
+                    });
+                    ui.separator();
+                }
+                egui::CollapsingHeader::new("Subtitle overlay").show(ui, |ui| {
+                    self.subtitle_overlay.settings(ui, &mut self.player);
+                });
+                ui.separator();
+                self.video_view.settings(ui);
+                ui.separator();
+                if self.player.playlist().is_some() {
+                    egui::CollapsingHeader::new("Playlist")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            self.playlist_panel.show(ui, &mut self.player);
+                        });
+                    ui.separator();
+                }
+                if self.player.media_info().is_some() {
+                    egui::CollapsingHeader::new("Media info").show(ui, |ui| {
+                        self.media_info_panel.show(ui, &mut self.player);
+                    });
+                    ui.separator();
+                }
+                if ui.button("Quit").clicked() {
+                    *app_state = AppState::Exit;
+                }
+            });
+        }
+        // the overlay follows the libvlc subtitle delay
+        let delay = self.player.vlc().map_or(0, |vlc| vlc.subtitle_delay());
+        let time = self.player.source().time().map(|time| time - delay);
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().fill(egui::Color32::BLACK))
+            .show(ctx, |ui| {
+                if let Some(rect) = self.video_view.show(ui, &self.player) {
+                    self.subtitle_overlay.show(ui, rect, time);
+                }
+            });
+    }
+
+    /// New frames from libvlc wake the window on their own, only what changes without
+    /// them is scheduled here.
+    fn repaint_after(&self) -> Option<Duration> {
+        let source = self.player.source();
+        if !source.is_playing() {
+            return None;
+        }
+        let mut after = if self.show_ui && self.player.audio_levels().is_some() {
+            METER_REFRESH
+        } else {
+            CLOCK_REFRESH
+        };
+        if let Some(next_frame) = source.next_frame_in() {
+            after = after.min(next_frame);
+        }
+        Some(after)
+    }
+
+    fn update_textures(&mut self, gl: &glow::Context, painter: &mut egui_glow::Painter) {
+        self.video_view
+            .update_textures(gl, painter, &mut self.player);
+    }
+
+    fn exit(&mut self) {
+        // ends the audio stream, which completes a WAV file written by the audio sink
+        self.player.source_mut().stop();
+    }
+
+    fn window_title(&self) -> Option<String> {
+        let info = self.player.media_info()?;
+        let title = info.display_title()?;
+        Some(format!("{} - Neovideo", title))
+    }
+}
+
+impl NeovideoApp {
+    pub fn new(mut player: Player, show_ui: bool) -> Self {
+        let settings = Settings::load();
+        let audio_device_panel = AudioDevicePanel::new(&settings, &mut player);
+        let equalizer_panel = EqualizerPanel::new(&settings, &mut player);
+        Self {
+            player,
+            audio_device_panel,
+            audio_panel: AudioPanel::default(),
+            control_panel: ControlPanel::default(),
+            equalizer_panel,
+            media_info_panel: MediaInfoPanel::default(),
+            playlist_panel: PlaylistPanel::default(),
+            subtitle_overlay: SubtitleOverlay::default(),
+            subtitle_panel: SubtitlePanel::default(),
+            track_menu: TrackMenu::default(),
+            video_view: VideoView::default(),
+            show_ui,
+            settings,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use neovideo_vlc::{vlc::Meta, Media, Repeat};
+
+use crate::player::Player;
+
+enum Action {
+    Play(usize),
+    Remove(usize),
+    Move(usize, usize),
+    Previous,
+    Next,
+}
+
+#[derive(Default)]
+pub struct PlaylistPanel {
+    add_path: String,
+    error: Option<String>,
+    /// Row being dragged to a new place.
+    dragging: Option<usize>,
+}
+
+impl PlaylistPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        let mut actions = Vec::new();
+        let playlist = match player.playlist_mut() {
+            Some(playlist) => playlist,
+            None => return,
+        };
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.add_path);
+        });
+        ui.horizontal(|ui| {
+            let path = self.add_path.trim();
+            if path.is_empty() {
+                return;
+            }
+            if ui.button("Add").clicked() {
+                self.error = playlist.add_url(path).err().map(|e| e.to_string());
+            }
+            // M3U, PLS or XSPF by extension
+            if ui.button("Load").clicked() {
+                self.error = playlist.import(path).err().map(|e| e.to_string());
+            }
+            if ui.button("Save").clicked() {
+                self.error = playlist.export(path).err().map(|e| e.to_string());
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Previous").clicked() {
+                actions.push(Action::Previous);
+            }
+            if ui.button("Next").clicked() {
+                actions.push(Action::Next);
+            }
+            let mut shuffle = playlist.shuffle();
+            if ui.checkbox(&mut shuffle, "Shuffle").changed() {
+                playlist.set_shuffle(shuffle);
+            }
+        });
+        let repeat = playlist.repeat();
+        egui::ComboBox::from_label("Repeat")
+            .selected_text(repeat.name())
+            .show_ui(ui, |ui| {
+                for r in Repeat::ALL {
+                    if ui.selectable_label(r == repeat, r.name()).clicked() {
+                        playlist.set_repeat(r);
+                    }
+                }
+            });
+
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                let mut rows = Vec::with_capacity(playlist.len());
+                for index in 0..playlist.len() {
+                    let name = playlist
+                        .get(index)
+                        .map_or_else(|| "?".to_owned(), |media| row_name(&media));
+                    let current = playlist.current() == Some(index);
+                    let response = ui
+                        .horizontal(|ui| {
+                            if ui.small_button("x").clicked() {
+                                actions.push(Action::Remove(index));
+                            }
+                            ui.selectable_label(current, name)
+                                .interact(egui::Sense::drag())
+                        })
+                        .inner;
+                    if response.double_clicked() {
+                        actions.push(Action::Play(index));
+                    }
+                    if response.drag_started() {
+                        self.dragging = Some(index);
+                    }
+                    rows.push(response.rect);
+                }
+                if let Some(from) = self.dragging {
+                    self.drag_row(ui, &rows, from, &mut actions);
+                }
+            });
+
+        for action in actions {
+            let result = match action {
+                Action::Play(index) => player.play_index(index, &[]),
+                Action::Remove(index) => {
+                    if let Some(playlist) = player.playlist_mut() {
+                        playlist.remove(index);
+                    }
+                    Ok(())
+                }
+                Action::Move(from, to) => {
+                    if let Some(playlist) = player.playlist_mut() {
+                        playlist.move_item(from, to);
+                    }
+                    Ok(())
+                }
+                Action::Previous => player.play_previous().map(|_| ()),
+                Action::Next => player.play_next(&[]).map(|_| ()),
+            };
+            if let Err(e) = result {
+                self.error = Some(e.to_string());
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    /// Marks where the dragged row would land, moves it there on release.
+    fn drag_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        rows: &[egui::Rect],
+        from: usize,
+        actions: &mut Vec<Action>,
+    ) {
+        let (pointer, released) = {
+            let input = ui.input();
+            (input.pointer.hover_pos(), input.pointer.any_released())
+        };
+        let (pointer, first, last) = match (pointer, rows.first(), rows.last()) {
+            (Some(pointer), Some(first), Some(last)) => (pointer, first, last),
+            _ => {
+                self.dragging = None;
+                return;
+            }
+        };
+        let to = if pointer.y < first.top() {
+            0
+        } else {
+            rows.iter()
+                .position(|row| pointer.y < row.bottom())
+                .unwrap_or(rows.len() - 1)
+        };
+        if released {
+            self.dragging = None;
+            if to != from {
+                actions.push(Action::Move(from, to));
+            }
+            return;
+        }
+        // the row moves below the target when dragged down, above it when dragged up
+        let y = if to > from {
+            rows[to].bottom()
+        } else {
+            rows[to].top()
+        };
+        let stroke = ui.visuals().selection.stroke;
+        ui.painter().line_segment(
+            [egui::pos2(first.left(), y), egui::pos2(last.right(), y)],
+            stroke,
+        );
+    }
+}
+
+/// `Artist - Title` once known, the file name otherwise.
+fn row_name(media: &Media) -> String {
+    match (media.meta(Meta::Artist), media.meta(Meta::Title)) {
+        (Some(artist), Some(title)) => format!("{} - {}", artist, title),
+        (None, Some(title)) => title,
+        _ => media
+            .mrl()
+            .map_or_else(|| "?".to_owned(), |mrl| display_name(&mrl)),
+    }
+}
+
+/// The file name of `mrl`, with percent escapes decoded.
+fn display_name(mrl: &str) -> String {
+    let name = mrl.trim_end_matches('/').rsplit('/').next().unwrap_or(mrl);
+    let mut bytes = Vec::with_capacity(name.len());
+    let mut rest = name.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let escaped = match tail {
+            [h, l, ..] if b == b'%' => std::str::from_utf8(&[*h, *l])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
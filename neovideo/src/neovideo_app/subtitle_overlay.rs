@@ -0,0 +1,229 @@
+use egui::text::{LayoutJob, TextFormat};
+use neovideo_vlc::subtitle::{CueAlign, CueLine};
+use neovideo_vlc::{vlc::TrackType, Cue, Subtitles};
+
+use crate::player::Player;
+
+pub struct SubtitleStyle {
+    /// Text height as a fraction of the video height.
+    pub font_scale: f32,
+    pub color: egui::Color32,
+    pub outline: bool,
+    /// A translucent box behind the text.
+    pub background: bool,
+    /// Distance of bottom cues from the bottom edge as a fraction of the video height.
+    pub margin: f32,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        SubtitleStyle {
+            font_scale: 0.05,
+            color: egui::Color32::WHITE,
+            outline: true,
+            background: false,
+            margin: 0.05,
+        }
+    }
+}
+
+/// Draws SRT and WebVTT cues over the video, timed by the player clock.
+#[derive(Default)]
+pub struct SubtitleOverlay {
+    subtitles: Option<Subtitles>,
+    style: SubtitleStyle,
+    load_path: String,
+    /// The libvlc subtitle track turned off while the loaded subtitles show, selected
+    /// again by "Clear".
+    replaced_track: Option<i32>,
+    error: Option<String>,
+}
+
+impl SubtitleOverlay {
+    /// Paints the cues active at `time`, in milliseconds, over the video in `rect`.
+    pub fn show(&self, ui: &egui::Ui, rect: egui::Rect, time: Option<i64>) {
+        let (subtitles, time) = match (&self.subtitles, time) {
+            (Some(subtitles), Some(time)) => (subtitles, time),
+            _ => return,
+        };
+        let painter = ui.painter_at(rect);
+        let font_size = (rect.height() * self.style.font_scale).max(8.0);
+        let mut bottom = rect.bottom() - rect.height() * self.style.margin;
+        // later cues stack above earlier ones
+        for cue in subtitles.active(time) {
+            let galley = ui
+                .fonts()
+                .layout_job(self.layout(cue, rect, font_size, None, false));
+            let size = galley.rect.size();
+            let x = match (cue.settings.position, cue.settings.align) {
+                (Some(position), _) => rect.left() + rect.width() * position / 100.0,
+                (None, CueAlign::Start) => rect.left() + rect.width() * 0.05,
+                (None, CueAlign::Center) => rect.center().x,
+                (None, CueAlign::End) => rect.right() - rect.width() * 0.05,
+            };
+            let line_height = font_size * 1.2;
+            let top = match cue.settings.line {
+                Some(CueLine::Percent(percent)) => rect.top() + rect.height() * percent / 100.0,
+                Some(CueLine::Number(line)) if line >= 0 => rect.top() + line as f32 * line_height,
+                Some(CueLine::Number(line)) => {
+                    rect.bottom() + (line + 1) as f32 * line_height - size.y
+                }
+                None => {
+                    bottom -= size.y;
+                    bottom
+                }
+            };
+            let top = top.min(rect.bottom() - size.y).max(rect.top());
+            let pos = egui::pos2(x, top);
+            if self.style.background {
+                let padding = egui::vec2(font_size * 0.25, font_size * 0.1);
+                painter.rect_filled(
+                    galley.rect.translate(pos.to_vec2()).expand2(padding),
+                    font_size * 0.1,
+                    egui::Color32::from_black_alpha(160),
+                );
+            }
+            if self.style.outline {
+                let shadow = ui.fonts().layout_job(self.layout(
+                    cue,
+                    rect,
+                    font_size,
+                    Some(egui::Color32::BLACK),
+                    false,
+                ));
+                let width = (font_size / 16.0).max(1.0);
+                for (dx, dy) in [
+                    (-1.0, -1.0),
+                    (0.0, -1.0),
+                    (1.0, -1.0),
+                    (-1.0, 0.0),
+                    (1.0, 0.0),
+                    (-1.0, 1.0),
+                    (0.0, 1.0),
+                    (1.0, 1.0),
+                ] {
+                    painter.galley(pos + egui::vec2(dx, dy) * width, shadow.clone());
+                }
+            }
+            painter.galley(pos, galley);
+            // egui has no bold font, bold spans are drawn a second time slightly offset
+            if cue.lines.iter().flatten().any(|span| span.bold) {
+                let bold = ui
+                    .fonts()
+                    .layout_job(self.layout(cue, rect, font_size, None, true));
+                painter.galley(pos + egui::vec2((font_size / 24.0).max(1.0), 0.0), bold);
+            }
+        }
+    }
+
+    /// The text of `cue`, all in `color` if given. With `bold_only` other spans are
+    /// laid out the same but transparent.
+    fn layout(
+        &self,
+        cue: &Cue,
+        rect: egui::Rect,
+        font_size: f32,
+        color: Option<egui::Color32>,
+        bold_only: bool,
+    ) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        job.wrap.max_width = rect.width() * cue.settings.size.unwrap_or(90.0) / 100.0;
+        job.halign = match cue.settings.align {
+            CueAlign::Start => egui::Align::LEFT,
+            CueAlign::Center => egui::Align::Center,
+            CueAlign::End => egui::Align::RIGHT,
+        };
+        for (i, line) in cue.lines.iter().enumerate() {
+            let newline = if i + 1 < cue.lines.len() { "\n" } else { "" };
+            let spans = line.iter().map(Some).chain(std::iter::once(None));
+            for span in spans {
+                let (text, format) = match span {
+                    Some(span) => {
+                        let span_color = match (color, span.color) {
+                            _ if bold_only && !span.bold => egui::Color32::TRANSPARENT,
+                            (Some(color), _) => color,
+                            (None, Some([r, g, b])) => egui::Color32::from_rgb(r, g, b),
+                            (None, None) => self.style.color,
+                        };
+                        let underline = if span.underline {
+                            egui::Stroke::new(font_size / 16.0, span_color)
+                        } else {
+                            egui::Stroke::none()
+                        };
+                        let format = TextFormat {
+                            font_id: egui::FontId::proportional(font_size),
+                            color: span_color,
+                            italics: span.italic,
+                            underline,
+                            ..Default::default()
+                        };
+                        (span.text.as_str(), format)
+                    }
+                    None => (
+                        newline,
+                        TextFormat {
+                            font_id: egui::FontId::proportional(font_size),
+                            ..Default::default()
+                        },
+                    ),
+                };
+                job.append(text, 0.0, format);
+            }
+        }
+        job
+    }
+
+    /// Loading files and the text style.
+    pub fn settings(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.load_path);
+            let path = self.load_path.trim();
+            if ui.button("Load").clicked() && !path.is_empty() {
+                match Subtitles::load(path) {
+                    Ok(subtitles) => {
+                        self.error = None;
+                        // libvlc would draw the same text a second time
+                        let playing = player.vlc().filter(|vlc| vlc.media_player().is_some());
+                        if let Some(vlc) = playing {
+                            if self.subtitles.is_none() {
+                                self.replaced_track = vlc.track(TrackType::Text);
+                            }
+                            if let Err(e) = vlc.set_track(TrackType::Text, None) {
+                                self.error = Some(e.to_string());
+                            }
+                        }
+                        self.subtitles = Some(subtitles);
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+            if ui
+                .add_enabled(self.subtitles.is_some(), egui::Button::new("Clear"))
+                .clicked()
+            {
+                self.subtitles = None;
+                if let (Some(id), Some(vlc)) = (self.replaced_track.take(), player.vlc()) {
+                    self.error = vlc
+                        .set_track(TrackType::Text, Some(id))
+                        .err()
+                        .map(|e| e.to_string());
+                }
+            }
+        });
+        if let Some(subtitles) = &self.subtitles {
+            ui.label(format!("{} cues", subtitles.cues().len()));
+        }
+        let style = &mut self.style;
+        ui.add(egui::Slider::new(&mut style.font_scale, 0.02..=0.12).text("Size"));
+        ui.add(egui::Slider::new(&mut style.margin, 0.0..=0.5).text("Bottom margin"));
+        ui.horizontal(|ui| {
+            ui.label("Colour");
+            ui.color_edit_button_srgba(&mut style.color);
+            ui.checkbox(&mut style.outline, "Outline");
+            ui.checkbox(&mut style.background, "Box");
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
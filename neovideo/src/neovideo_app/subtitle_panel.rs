@@ -0,0 +1,84 @@
+use crate::player::Player;
+
+/// Milliseconds one press of the delay buttons or the G and H keys moves subtitles by.
+const DELAY_STEP: i64 = 50;
+
+#[derive(Default)]
+pub struct SubtitlePanel {
+    load_path: String,
+    error: Option<String>,
+}
+
+impl SubtitlePanel {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.load_path);
+            let path = self.load_path.trim();
+            if ui.button("Load").clicked() && !path.is_empty() {
+                self.error = vlc.load_subtitle_file(path).err().map(|e| e.to_string());
+            }
+        });
+        let mut sidecar = vlc.sidecar_subtitles();
+        if ui
+            .checkbox(&mut sidecar, "Load subtitle files next to the media")
+            .changed()
+        {
+            vlc.set_sidecar_subtitles(sidecar);
+        }
+
+        let delay = vlc.subtitle_delay();
+        let mut new_delay = None;
+        ui.horizontal(|ui| {
+            ui.label(format!("Delay {:+.3} s", delay as f64 / 1000.0));
+            if ui.button(format!("-{} ms", DELAY_STEP)).clicked() {
+                new_delay = Some(delay - DELAY_STEP);
+            }
+            if ui
+                .add_enabled(delay != 0, egui::Button::new("Reset"))
+                .clicked()
+            {
+                new_delay = Some(0);
+            }
+            if ui.button(format!("+{} ms", DELAY_STEP)).clicked() {
+                new_delay = Some(delay + DELAY_STEP);
+            }
+        });
+        if let Some(new_delay) = new_delay {
+            self.error = vlc
+                .set_subtitle_delay(new_delay)
+                .err()
+                .map(|e| e.to_string());
+        }
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    /// Moves subtitles with the G and H keys as in VLC, unless a text field has the
+    /// focus. Works with the panel closed or the UI hidden.
+    pub fn handle_keys(&mut self, ctx: &egui::Context, player: &mut Player) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let step = {
+            let input = ctx.input();
+            if input.key_pressed(egui::Key::G) {
+                -DELAY_STEP
+            } else if input.key_pressed(egui::Key::H) {
+                DELAY_STEP
+            } else {
+                return;
+            }
+        };
+        if let Some(vlc) = player.vlc() {
+            let delay = vlc.subtitle_delay() + step;
+            self.error = vlc.set_subtitle_delay(delay).err().map(|e| e.to_string());
+        }
+    }
+}
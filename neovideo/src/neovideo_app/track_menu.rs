@@ -0,0 +1,83 @@
+use neovideo_vlc::{vlc::TrackType, vlcvideo::VLCVideo, NeovideoError};
+
+use crate::player::Player;
+
+const MENUS: [(TrackType, &str); 3] = [
+    (TrackType::Video, "Video"),
+    (TrackType::Audio, "Audio"),
+    (TrackType::Text, "Subtitles"),
+];
+
+#[derive(Default)]
+pub struct TrackMenu {
+    error: Option<String>,
+}
+
+impl TrackMenu {
+    pub fn show(&mut self, ui: &mut egui::Ui, player: &mut Player) {
+        let vlc = match player.vlc() {
+            Some(vlc) => vlc,
+            None => return,
+        };
+        ui.horizontal(|ui| {
+            for (track_type, label) in MENUS {
+                ui.menu_button(label, |ui| {
+                    if let Err(e) = track_choices(ui, vlc, track_type) {
+                        self.error = Some(e.to_string());
+                    }
+                });
+            }
+        });
+        egui::CollapsingHeader::new("Track details").show(ui, |ui| {
+            let tracks = vlc.tracks();
+            if tracks.is_empty() {
+                ui.label("No tracks known yet");
+            }
+            for track in tracks {
+                let label = ui.label(format!("#{} {}", track.id, track.summary()));
+                let mut hover = Vec::new();
+                if let Some(codec_name) = &track.codec_name {
+                    hover.push(codec_name.clone());
+                }
+                if let Some(description) = &track.description {
+                    hover.push(description.clone());
+                }
+                if !hover.is_empty() {
+                    label.on_hover_text(hover.join("\n"));
+                }
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
+
+/// Radio entries for the tracks of `track_type`, selecting one closes the menu.
+fn track_choices(
+    ui: &mut egui::Ui,
+    vlc: &mut VLCVideo,
+    track_type: TrackType,
+) -> Result<(), NeovideoError> {
+    let descriptions = vlc.track_descriptions(track_type);
+    if descriptions.is_empty() {
+        ui.label("None");
+        return Ok(());
+    }
+    let selected = vlc.track(track_type).unwrap_or(-1);
+    for description in descriptions {
+        if ui
+            .radio(description.id == selected, &description.name)
+            .clicked()
+        {
+            ui.close_menu();
+            let id = if description.id < 0 {
+                None
+            } else {
+                Some(description.id)
+            };
+            vlc.set_track(track_type, id)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use glow::HasContext;
+use neovideo_vlc::{
+    swvideo::{Chroma, CpuFrame},
+    vlcvideo::{TextureRender, VideoFrame},
+    AspectRatio, ColorInfo, ScaleMode, ToneMapping, VideoQuad,
+};
+
+use crate::player::Player;
+
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 1.0..=4.0;
+
+/// The video picture in the central panel, laid out according to the scale mode.
+#[derive(Default)]
+pub struct VideoView {
+    scale_mode: ScaleMode,
+    aspect_ratio: AspectRatio,
+    texture: Option<egui::TextureId>,
+    /// GL textures hold the picture bottom-up, CPU frames top-down.
+    bottom_up: bool,
+    /// Every texture the decoder rendered to so far, registered once each since the
+    /// painter deletes textures it replaces.
+    registered_textures: HashMap<glow::Texture, egui::TextureId>,
+    /// The texture CPU frames are uploaded to.
+    upload_texture: Option<(glow::Texture, egui::TextureId)>,
+    /// Colour of the decoded texture, `None` for sources delivering sRGB.
+    color_info: Option<ColorInfo>,
+    tone_mapping: ToneMapping,
+    /// SDR copy of HDR and wide gamut frames.
+    converted: Option<ConvertedTexture>,
+    error: Option<String>,
+}
+
+/// A texture drawn into by `TextureRender`, registered with the painter once.
+struct ConvertedTexture {
+    texture: glow::Texture,
+    framebuffer: glow::Framebuffer,
+    id: egui::TextureId,
+    size: (u32, u32),
+    /// The texture and operator the copy was drawn from.
+    source: Option<(u32, ToneMapping)>,
+}
+
+impl VideoView {
+    pub fn update_textures(
+        &mut self,
+        gl: &glow::Context,
+        painter: &mut egui_glow::Painter,
+        player: &mut Player,
+    ) {
+        let mut gl_texture = None;
+        match player.video_frame() {
+            Some((VideoFrame::Texture(tex), update)) => gl_texture = Some((tex, update)),
+            Some((VideoFrame::Cpu(frame), update)) => {
+                let (texture, id) = match self.upload_texture {
+                    Some(upload_texture) if !update => upload_texture,
+                    Some((texture, id)) => {
+                        upload(gl, texture, frame);
+                        (texture, id)
+                    }
+                    None => {
+                        let texture = match unsafe { gl.create_texture() } {
+                            Ok(texture) => texture,
+                            Err(e) => {
+                                self.error = Some(e);
+                                return;
+                            }
+                        };
+                        upload(gl, texture, frame);
+                        (texture, painter.register_native_texture(texture))
+                    }
+                };
+                self.upload_texture = Some((texture, id));
+                self.texture = Some(id);
+                self.bottom_up = false;
+                self.color_info = None;
+                self.error = None;
+            }
+            None => {}
+        }
+        if let Some((tex, update)) = gl_texture {
+            self.show_gl_texture(gl, painter, player, tex, update);
+        }
+    }
+
+    /// Shows a texture of the libvlc OpenGL output, through an SDR copy where its
+    /// colour needs converting. `update` tells whether `tex` holds a new frame.
+    fn show_gl_texture(
+        &mut self,
+        gl: &glow::Context,
+        painter: &mut egui_glow::Painter,
+        player: &mut Player,
+        tex: u32,
+        update: bool,
+    ) {
+        self.color_info = player.vlc().and_then(|vlc| vlc.color_info());
+        let video_size = player.source().video_size();
+        let converted = match (self.color_info, video_size, player.texture_render()) {
+            (Some(color_info), Some(video_size), Some(render)) if color_info.needs_conversion() => {
+                render.set_color_info(color_info);
+                self.convert(gl, painter, render, tex, video_size, update)
+                    .map(Some)
+            }
+            _ => Ok(None),
+        };
+        // a failed conversion shows the texture as is, until a later frame converts
+        let converted = match converted {
+            Ok(id) => {
+                self.error = None;
+                id
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        };
+        let id = converted.unwrap_or_else(|| {
+            let texture = unsafe { glow::Context::create_texture_from_gl_name(tex) };
+            *self
+                .registered_textures
+                .entry(texture)
+                .or_insert_with(|| painter.register_native_texture(texture))
+        });
+        self.texture = Some(id);
+        self.bottom_up = true;
+    }
+
+    /// Draws `tex` through `render` into the SDR copy, which stays bottom-up like `tex`.
+    /// Repaints without a new frame reuse the copy.
+    fn convert(
+        &mut self,
+        gl: &glow::Context,
+        painter: &mut egui_glow::Painter,
+        render: &mut TextureRender,
+        tex: u32,
+        video_size: (u32, u32),
+        update: bool,
+    ) -> Result<egui::TextureId, String> {
+        let converted = match &mut self.converted {
+            Some(converted) => converted,
+            None => {
+                let texture = unsafe { gl.create_texture()? };
+                let framebuffer = match unsafe { gl.create_framebuffer() } {
+                    Ok(framebuffer) => framebuffer,
+                    Err(e) => {
+                        unsafe { gl.delete_texture(texture) };
+                        return Err(e);
+                    }
+                };
+                self.converted.insert(ConvertedTexture {
+                    texture,
+                    framebuffer,
+                    id: painter.register_native_texture(texture),
+                    size: (0, 0),
+                    source: None,
+                })
+            }
+        };
+        let source = (tex, self.tone_mapping);
+        if !update && converted.source == Some(source) {
+            return Ok(converted.id);
+        }
+        let (width, height) = (video_size.0 as i32, video_size.1 as i32);
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(converted.framebuffer));
+            if converted.size != video_size {
+                gl.bind_texture(glow::TEXTURE_2D, Some(converted.texture));
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::RGBA8 as i32,
+                    width,
+                    height,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    None,
+                );
+                set_sampling(gl);
+                gl.bind_texture(glow::TEXTURE_2D, None);
+                gl.framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    glow::COLOR_ATTACHMENT0,
+                    glow::TEXTURE_2D,
+                    Some(converted.texture),
+                    0,
+                );
+                converted.size = video_size;
+            }
+            gl.viewport(0, 0, width, height);
+        }
+        render.set_tone_mapping(self.tone_mapping);
+        render.set_scale_mode(ScaleMode::Stretch);
+        render.set_aspect_ratio(AspectRatio::Source);
+        render.draw_video_frame(tex, video_size, video_size);
+        unsafe {
+            // the painter sets its own viewport but expects the rest as it left it
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.active_texture(glow::TEXTURE0);
+        }
+        converted.source = Some(source);
+        Ok(converted.id)
+    }
+
+    /// Paints the latest frame, returns the part of the window the video covers.
+    pub fn show(&self, ui: &mut egui::Ui, player: &Player) -> Option<egui::Rect> {
+        let (texture, video_size) = match (self.texture, player.source().video_size()) {
+            (Some(texture), Some(video_size)) => (texture, video_size),
+            _ => return None,
+        };
+        let viewport = ui.max_rect();
+        let quad = VideoQuad::new(
+            video_size,
+            (viewport.width(), viewport.height()),
+            self.scale_mode,
+            self.aspect_ratio,
+        )?;
+        let [left, top, right, bottom] = quad.rect;
+        let rect = egui::Rect::from_min_max(
+            viewport.min + egui::vec2(left, top),
+            viewport.min + egui::vec2(right, bottom),
+        );
+        let [u0, v0, u1, v1] = quad.uv;
+        let uv = if self.bottom_up {
+            egui::Rect::from_min_max(egui::pos2(u0, 1.0 - v0), egui::pos2(u1, 1.0 - v1))
+        } else {
+            egui::Rect::from_min_max(egui::pos2(u0, v0), egui::pos2(u1, v1))
+        };
+        egui::Image::new(texture, rect.size())
+            .uv(uv)
+            .paint_at(ui, rect);
+        // Fill and Zoom overflow the window
+        Some(rect.intersect(viewport))
+    }
+
+    /// Scale mode, aspect ratio and tone mapping selectors.
+    pub fn settings(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_label("Scale")
+            .selected_text(scale_mode_name(self.scale_mode))
+            .show_ui(ui, |ui| {
+                for mode in [
+                    ScaleMode::Fit,
+                    ScaleMode::Fill,
+                    ScaleMode::Stretch,
+                    ScaleMode::Zoom(1.5),
+                ] {
+                    let selected =
+                        std::mem::discriminant(&mode) == std::mem::discriminant(&self.scale_mode);
+                    if ui
+                        .selectable_label(selected, scale_mode_name(mode))
+                        .clicked()
+                        && !selected
+                    {
+                        self.scale_mode = mode;
+                    }
+                }
+            });
+        if let ScaleMode::Zoom(zoom) = &mut self.scale_mode {
+            ui.add(egui::Slider::new(zoom, ZOOM_RANGE).text("Zoom"));
+        }
+        egui::ComboBox::from_label("Aspect")
+            .selected_text(self.aspect_ratio.name())
+            .show_ui(ui, |ui| {
+                for aspect_ratio in AspectRatio::ALL {
+                    ui.selectable_value(&mut self.aspect_ratio, aspect_ratio, aspect_ratio.name());
+                }
+            });
+        if let Some(color_info) = self.color_info {
+            ui.label(color_info.describe());
+            ui.add_enabled_ui(color_info.is_hdr(), |ui| {
+                egui::ComboBox::from_label("Tone mapping")
+                    .selected_text(self.tone_mapping.name())
+                    .show_ui(ui, |ui| {
+                        for tone_mapping in ToneMapping::ALL {
+                            ui.selectable_value(
+                                &mut self.tone_mapping,
+                                tone_mapping,
+                                tone_mapping.name(),
+                            );
+                        }
+                    });
+            });
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+}
+
+fn scale_mode_name(mode: ScaleMode) -> &'static str {
+    match mode {
+        ScaleMode::Fit => "Fit",
+        ScaleMode::Fill => "Fill",
+        ScaleMode::Stretch => "Stretch",
+        ScaleMode::Zoom(_) => "Zoom",
+    }
+}
+
+/// Copies `frame` into `texture`, RGBA frames straight from their padded rows.
+fn upload(gl: &glow::Context, texture: glow::Texture, frame: &CpuFrame) {
+    let (width, height) = (frame.width as i32, frame.height as i32);
+    let converted;
+    let (pixels, row_length) = match frame.chroma {
+        Chroma::Rgba => (&frame.planes[0].data[..], frame.planes[0].pitch as i32 / 4),
+        Chroma::I420 => {
+            converted = frame.to_rgba();
+            (&converted[..], width)
+        }
+    };
+    unsafe {
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, row_length);
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            width,
+            height,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            Some(pixels),
+        );
+        gl.pixel_store_i32(glow::UNPACK_ROW_LENGTH, 0);
+        set_sampling(gl);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+    }
+}
+
+/// Linear filtering without wrapping for the bound texture.
+unsafe fn set_sampling(gl: &glow::Context) {
+    gl.tex_parameter_i32(
+        glow::TEXTURE_2D,
+        glow::TEXTURE_MIN_FILTER,
+        glow::LINEAR as i32,
+    );
+    gl.tex_parameter_i32(
+        glow::TEXTURE_2D,
+        glow::TEXTURE_MAG_FILTER,
+        glow::LINEAR as i32,
+    );
+    gl.tex_parameter_i32(
+        glow::TEXTURE_2D,
+        glow::TEXTURE_WRAP_S,
+        glow::CLAMP_TO_EDGE as i32,
+    );
+    gl.tex_parameter_i32(
+        glow::TEXTURE_2D,
+        glow::TEXTURE_WRAP_T,
+        glow::CLAMP_TO_EDGE as i32,
+    );
+}
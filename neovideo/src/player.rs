@@ -0,0 +1,328 @@
+use std::{
+    path::Path,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use glutin::{
+    event_loop::{EventLoopProxy, EventLoopWindowTarget},
+    PossiblyCurrent, WindowedContext,
+};
+use neovideo_vlc::{
+    vlc::EventType,
+    vlc::Meta,
+    vlcvideo::{TextureRender, VLCVideo, VideoFrame},
+    AudioLevels, AudioSink, Event, EventManager, Media, MediaInfo, MediaPlayer, NeovideoError,
+    Playlist, Subscription, VideoSource,
+};
+
+use crate::UserEvent;
+
+/// Plays the queued media from a video source, libvlc rendering into textures shared
+/// with the window's GL context unless another source is given.
+pub struct Player {
+    source: Box<dyn VideoSource>,
+    /// `None` for sources that cannot open media.
+    playlist: Option<Playlist>,
+    /// Input options applied to every media started from the playlist.
+    media_options: Vec<String>,
+    /// Volume to apply once the audio output of the next media is up.
+    start_volume: Option<i32>,
+    pending_volume: Option<i32>,
+    event_sender: Sender<Event>,
+    events: Receiver<Event>,
+    /// Player events, kept for as long as the source plays with the same player.
+    subscription: Option<(MediaPlayer, Subscription)>,
+    media_subscription: Option<Subscription>,
+    /// Metadata of the playing media, refreshed as libvlc learns more.
+    media_info: Option<MediaInfo>,
+    /// Set while the audio goes to a level meter instead of the sound card.
+    audio_levels: Option<AudioLevels>,
+    /// Wakes the event loop for new frames and player events.
+    proxy: Option<EventLoopProxy<UserEvent>>,
+    /// Converts HDR and wide gamut textures of libvlc for the window, `None` for other
+    /// sources.
+    texture_render: Option<TextureRender>,
+}
+
+impl Player {
+    pub fn new<T>(
+        event_loop: &EventLoopWindowTarget<T>,
+        window_context: &WindowedContext<PossiblyCurrent>,
+        vlc_args: &[String],
+    ) -> Result<Self, NeovideoError> {
+        let video_decoder = VLCVideo::with_args(window_context.context(), event_loop, vlc_args)?;
+        let playlist = Playlist::new(video_decoder.instance())?;
+        let mut player = Player::with_source(Box::new(video_decoder));
+        player.playlist = Some(playlist);
+        player.texture_render = Some(TextureRender::new(window_context.context())?);
+        Ok(player)
+    }
+
+    pub fn with_source(source: Box<dyn VideoSource>) -> Self {
+        let (event_sender, events) = channel();
+        Player {
+            source,
+            playlist: None,
+            media_options: Vec::new(),
+            start_volume: None,
+            pending_volume: None,
+            event_sender,
+            events,
+            subscription: None,
+            media_subscription: None,
+            media_info: None,
+            audio_levels: None,
+            proxy: None,
+            texture_render: None,
+        }
+    }
+
+    /// Sends `UserEvent::Redraw` through `proxy` whenever the source has a new frame or
+    /// a player event is queued, set it before playing anything.
+    pub fn set_event_proxy(&mut self, proxy: EventLoopProxy<UserEvent>) {
+        let frame_proxy = proxy.clone();
+        self.source.set_frame_notifier(Some(Box::new(move || {
+            let _ = frame_proxy.send_event(UserEvent::Redraw);
+        })));
+        self.proxy = Some(proxy);
+    }
+
+    pub fn source(&self) -> &dyn VideoSource {
+        self.source.as_ref()
+    }
+
+    pub fn source_mut(&mut self) -> &mut dyn VideoSource {
+        self.source.as_mut()
+    }
+
+    /// The libvlc backend, `None` for other sources.
+    pub fn vlc(&mut self) -> Option<&mut VLCVideo> {
+        self.source.vlc()
+    }
+
+    pub fn texture_render(&mut self) -> Option<&mut TextureRender> {
+        self.texture_render.as_mut()
+    }
+
+    pub fn playlist(&self) -> Option<&Playlist> {
+        self.playlist.as_ref()
+    }
+
+    pub fn playlist_mut(&mut self) -> Option<&mut Playlist> {
+        self.playlist.as_mut()
+    }
+
+    /// Appends `path` to the playlist and plays it.
+    pub fn play<T>(&mut self, path: T) -> Result<(), NeovideoError>
+    where
+        T: AsRef<Path>,
+    {
+        let index = self.playlist.as_mut().ok_or_else(no_media)?.add_url(path)?;
+        self.play_index(index, &[])
+    }
+
+    /// Plays the playlist item at `index` with extra input `options`.
+    pub fn play_index(&mut self, index: usize, options: &[String]) -> Result<(), NeovideoError> {
+        let playlist = self.playlist.as_mut().ok_or_else(no_media)?;
+        let missing = || NeovideoError::InvalidArgument(format!("no playlist item {}", index));
+        playlist.set_current(index).ok_or_else(missing)?;
+        let mut media = playlist.current_media().ok_or_else(missing)?;
+        if !options.is_empty() {
+            // one-off options must not stick to the playlist item
+            media = media.duplicate()?;
+        }
+        let vlc = self.source.vlc().ok_or_else(no_media)?;
+        let options: Vec<&String> = self.media_options.iter().chain(options).collect();
+        vlc.play_with_options(&media, &options)?;
+        for e in vlc.sidecar_errors() {
+            eprintln!("neovideo: {}", e);
+        }
+        let player = vlc.media_player().cloned();
+        let media = vlc.media().cloned();
+        // a new audio sink comes with a new player, which needs its own subscription
+        let subscribed = matches!(
+            (&self.subscription, &player),
+            (Some((current, _)), Some(player)) if current.raw() == player.raw()
+        );
+        if !subscribed {
+            self.subscription = match player {
+                Some(player) => {
+                    let subscription = self.subscribe(
+                        player.event_manager(),
+                        &[
+                            EventType::MediaPlayerPlaying,
+                            EventType::MediaPlayerEndReached,
+                            EventType::MediaPlayerEncounteredError,
+                        ],
+                    )?;
+                    Some((player, subscription))
+                }
+                None => None,
+            };
+        }
+        self.media_subscription = match media {
+            Some(media) => {
+                media.parse_async();
+                Some(self.subscribe(
+                    media.event_manager(),
+                    &[
+                        EventType::MediaParsedChanged,
+                        EventType::MediaMetaChanged,
+                        EventType::MediaDurationChanged,
+                    ],
+                )?)
+            }
+            None => None,
+        };
+        self.refresh_media_info();
+        self.pending_volume = self.start_volume;
+        Ok(())
+    }
+
+    /// Queues events of `types` for `poll_events`, waking the event loop for each.
+    fn subscribe(
+        &self,
+        manager: EventManager,
+        types: &[EventType],
+    ) -> Result<Subscription, NeovideoError> {
+        let sender = self.event_sender.clone();
+        let proxy = self.proxy.clone();
+        manager.attach(types, move |event| {
+            if sender.send(event).is_ok() {
+                if let Some(proxy) = &proxy {
+                    let _ = proxy.send_event(UserEvent::Redraw);
+                }
+            }
+        })
+    }
+
+    /// Metadata of the playing media, `None` for sources without media.
+    pub fn media_info(&self) -> Option<&MediaInfo> {
+        self.media_info.as_ref()
+    }
+
+    /// Changes metadata of the playing media and writes it to the file.
+    pub fn save_meta(&mut self, values: &[(Meta, String)]) -> Result<(), NeovideoError> {
+        let media = self.current_media().ok_or_else(no_media)?;
+        for (meta, value) in values {
+            media.set_meta(*meta, value);
+        }
+        let result = media.save_meta();
+        self.refresh_media_info();
+        result
+    }
+
+    fn current_media(&mut self) -> Option<Media> {
+        self.source.vlc()?.media().cloned()
+    }
+
+    fn refresh_media_info(&mut self) {
+        self.media_info = self.current_media().map(|media| media.info());
+    }
+
+    pub fn set_media_options(&mut self, options: Vec<String>) {
+        self.media_options = options;
+    }
+
+    /// Sends the audio of the media played from now on to `sink`, `levels` is the read
+    /// side of a level meter sink.
+    pub fn set_audio_sink(
+        &mut self,
+        sink: Box<dyn AudioSink>,
+        levels: Option<AudioLevels>,
+    ) -> Result<(), NeovideoError> {
+        self.source
+            .vlc()
+            .ok_or_else(no_media)?
+            .set_audio_sink(Some(sink));
+        self.audio_levels = levels;
+        Ok(())
+    }
+
+    pub fn audio_levels(&self) -> Option<&AudioLevels> {
+        self.audio_levels.as_ref()
+    }
+
+    pub fn set_start_volume(&mut self, volume: Option<i32>) {
+        self.start_volume = volume;
+    }
+
+    /// Plays the next playlist item with extra input `options`, returns `false` at the
+    /// end of the playlist.
+    pub fn play_next(&mut self, options: &[String]) -> Result<bool, NeovideoError> {
+        match self.playlist.as_mut().and_then(|p| p.next_item()) {
+            Some(index) => self.play_index(index, options).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Plays the previous playlist item, returns `false` if there is none.
+    pub fn play_previous(&mut self) -> Result<bool, NeovideoError> {
+        match self.playlist.as_mut().and_then(|p| p.previous_item()) {
+            Some(index) => self.play_index(index, &[]).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Goes on after the current item ended, honouring the repeat mode unless
+    /// `skip_current` is set.
+    fn advance(&mut self, skip_current: bool) {
+        let mut skip = skip_current;
+        let len = self.playlist.as_ref().map_or(0, |p| p.len());
+        // a broken media must not stop the rest of the playlist
+        for _ in 0..len {
+            let next =
+                self.playlist
+                    .as_mut()
+                    .and_then(|p| if skip { p.next_item() } else { p.advance() });
+            let index = match next {
+                Some(index) => index,
+                None => return,
+            };
+            match self.play_index(index, &[]) {
+                Ok(()) => return,
+                Err(e) => {
+                    eprintln!("neovideo: {}", e);
+                    skip = true;
+                }
+            }
+        }
+    }
+
+    /// Handles player events, call once per event loop iteration.
+    pub fn poll_events(&mut self) {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                Event::Playing => {
+                    self.refresh_media_info();
+                    let volume = self.pending_volume.take();
+                    if let (Some(volume), Some(vlc)) = (volume, self.source.vlc()) {
+                        if let Err(e) = vlc.set_volume(volume) {
+                            eprintln!("neovideo: {}", e);
+                        }
+                    }
+                }
+                Event::EndReached => self.advance(false),
+                // skip the broken item even when repeating it
+                Event::EncounteredError => self.advance(true),
+                Event::MediaParsedChanged(_)
+                | Event::MediaMetaChanged(_)
+                | Event::MediaDurationChanged(_) => self.refresh_media_info(),
+                _ => {}
+            }
+        }
+    }
+
+    /// The latest frame and whether it changed since the last call, `None` before the
+    /// first frame.
+    pub fn video_frame(&mut self) -> Option<(VideoFrame<'_>, bool)> {
+        let mut update = false;
+        let frame = self.source.get_video_frame(&mut update)?;
+        Some((frame, update))
+    }
+}
+
+/// The error for sources that cannot open media, nothing can be playing from them.
+fn no_media() -> NeovideoError {
+    NeovideoError::NothingPlaying
+}
@@ -0,0 +1,162 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// User preferences kept as `key = value` lines in `neovideo/settings.conf` under the
+/// platform's config directory. Lines starting with `#` are comments, keys cannot
+/// contain `=` or line breaks.
+#[derive(Default)]
+pub struct Settings {
+    /// `None` when no config directory is known, nothing is saved then.
+    path: Option<PathBuf>,
+    values: Vec<(String, String)>,
+}
+
+impl Settings {
+    /// Reads the settings file, a missing or unreadable file gives empty settings.
+    pub fn load() -> Settings {
+        Settings::load_from(config_dir().map(|dir| dir.join("neovideo").join("settings.conf")))
+    }
+
+    /// Like `load` with the file at `path`, `None` never reads nor saves a file.
+    fn load_from(path: Option<PathBuf>) -> Settings {
+        let values = match &path {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(text) => parse(&text),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => {
+                    eprintln!("neovideo: '{}': {}", path.display(), e);
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        Settings { path, values }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set(&mut self, key: &str, value: String) {
+        match self.values.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => self.values.push((key.to_owned(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.values.retain(|(k, _)| k != key);
+    }
+
+    /// The entries whose key starts with `prefix`, with the prefix stripped.
+    pub fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.values
+            .iter()
+            .filter_map(move |(k, v)| Some((k.strip_prefix(prefix)?, v.as_str())))
+    }
+
+    /// Writes the settings file, creating its directory if needed.
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::from("# neovideo settings\n");
+        for (key, value) in &self.values {
+            text.push_str(&format!("{} = {}\n", key, value));
+        }
+        fs::write(path, text)
+    }
+}
+
+fn parse(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+        .collect()
+}
+
+/// `$XDG_CONFIG_HOME`, `%APPDATA%` on Windows or `~/.config`.
+fn config_dir() -> Option<PathBuf> {
+    let from_env = |name| {
+        env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|p: &PathBuf| p.is_absolute())
+    };
+    if let Some(dir) = from_env("XDG_CONFIG_HOME") {
+        return Some(dir);
+    }
+    if cfg!(windows) {
+        if let Some(dir) = from_env("APPDATA") {
+            return Some(dir);
+        }
+    }
+    from_env("HOME").map(|home| Path::new(&home).join(".config"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(values: &[(String, String)]) -> Vec<(&str, &str)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_skips_comments_and_trims() {
+        let text = "# neovideo settings\n\n  volume =  80 \nbad line\n#x = 1\nkey=a=b\nempty =\n";
+        assert_eq!(
+            pairs(&parse(text)),
+            [("volume", "80"), ("key", "a=b"), ("empty", "")]
+        );
+    }
+
+    #[test]
+    fn with_prefix_strips_the_prefix() {
+        let mut settings = Settings::default();
+        settings.set("equalizer.curve.Rock", "1".to_owned());
+        settings.set("equalizer.enabled", "true".to_owned());
+        settings.set("equalizer.curve.Jazz", "2".to_owned());
+        let curves: Vec<_> = settings.with_prefix("equalizer.curve.").collect();
+        assert_eq!(curves, [("Rock", "1"), ("Jazz", "2")]);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = env::temp_dir().join(format!("neovideo-settings-{}", std::process::id()));
+        let path = dir.join("neovideo").join("settings.conf");
+        let mut settings = Settings::load_from(Some(path.clone()));
+        assert!(settings.get("volume").is_none());
+        settings.set("volume", "80".to_owned());
+        settings.set("equalizer.current", "0.0 1.5 -3.0".to_owned());
+        settings.set("volume", "90".to_owned());
+        settings.set("gone", "1".to_owned());
+        settings.remove("gone");
+        settings.save().unwrap();
+
+        let loaded = Settings::load_from(Some(path));
+        assert_eq!(pairs(&loaded.values), pairs(&settings.values));
+        assert_eq!(loaded.get("volume"), Some("90"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn without_a_path_nothing_is_saved() {
+        let mut settings = Settings::load_from(None);
+        settings.set("volume", "80".to_owned());
+        assert!(settings.save().is_ok());
+    }
+}
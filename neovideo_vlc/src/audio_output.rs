@@ -0,0 +1,67 @@
+use super::tools::from_cstr_ref;
+use super::vlc::{
+    libvlc_audio_output_device_list_release, libvlc_audio_output_device_t,
+    libvlc_audio_output_list_release, libvlc_audio_output_t,
+};
+
+/// An audio output module, such as `pulse` or `alsa`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioOutput {
+    /// The name to select the module with.
+    pub name: String,
+    pub description: String,
+}
+
+impl AudioOutput {
+    /// Copies a list from `libvlc_audio_output_list_get` and releases it.
+    ///
+    /// # Safety
+    /// `list` must be null or a list returned by libvlc that is not used afterwards.
+    pub(crate) unsafe fn from_list(list: *mut libvlc_audio_output_t) -> Vec<AudioOutput> {
+        let mut outputs = Vec::new();
+        let mut item = list;
+        while !item.is_null() {
+            if let Some(name) = from_cstr_ref((*item).psz_name) {
+                let description =
+                    from_cstr_ref((*item).psz_description).unwrap_or_else(|| name.clone());
+                outputs.push(AudioOutput { name, description });
+            }
+            item = (*item).p_next;
+        }
+        if !list.is_null() {
+            libvlc_audio_output_list_release(list);
+        }
+        outputs
+    }
+}
+
+/// A device of an audio output module, such as a sound card or a headset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioDevice {
+    /// The id to select the device with, stable across launches.
+    pub id: String,
+    pub description: String,
+}
+
+impl AudioDevice {
+    /// Copies a device list from libvlc and releases it.
+    ///
+    /// # Safety
+    /// `list` must be null or a list returned by libvlc that is not used afterwards.
+    pub(crate) unsafe fn from_list(list: *mut libvlc_audio_output_device_t) -> Vec<AudioDevice> {
+        let mut devices = Vec::new();
+        let mut item = list;
+        while !item.is_null() {
+            if let Some(id) = from_cstr_ref((*item).psz_device) {
+                let description =
+                    from_cstr_ref((*item).psz_description).unwrap_or_else(|| id.clone());
+                devices.push(AudioDevice { id, description });
+            }
+            item = (*item).p_next;
+        }
+        if !list.is_null() {
+            libvlc_audio_output_device_list_release(list);
+        }
+        devices
+    }
+}
@@ -0,0 +1,377 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::slice;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use libc::{c_char, c_int, c_uint, c_void};
+
+use super::error::{NeovideoError, Result};
+use super::media_player::MediaPlayer;
+use super::tools::ffi_guard;
+use super::vlc::{libvlc_audio_set_callbacks, libvlc_audio_set_format_callbacks};
+
+/// Layout of the PCM handed to an `AudioSink`: interleaved native endian signed
+/// 16 bit samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioFormat {
+    /// Samples per second and channel.
+    pub rate: u32,
+    pub channels: u32,
+}
+
+/// Receives decoded audio in place of a sound card, see `VLCVideo::set_audio_sink`.
+///
+/// All methods are called on a libvlc audio thread. Timestamps are in microseconds
+/// of the libvlc clock. A panic is caught before it reaches libvlc, after it the sink
+/// gets no more calls and later streams play without sound.
+pub trait AudioSink: Send {
+    /// A stream in `format` begins, returning `false` refuses it and leaves the media
+    /// without sound.
+    fn start(&mut self, format: AudioFormat) -> bool;
+
+    /// `samples` holds whole frames, one sample per channel each.
+    fn play(&mut self, samples: &[i16], pts: i64);
+
+    fn pause(&mut self, _pts: i64) {}
+
+    fn resume(&mut self, _pts: i64) {}
+
+    /// Drops buffered samples, as after a seek.
+    fn flush(&mut self, _pts: i64) {}
+
+    /// Plays out buffered samples, the stream is about to end.
+    fn drain(&mut self) {}
+
+    /// The stream started by `start` ended.
+    fn stop(&mut self) {}
+}
+
+/// Discards the audio, only counting the frames it receives. Playback keeps its pace
+/// as libvlc times the calls, which is all tests on machines without sound need.
+#[derive(Default)]
+pub struct NullSink {
+    frames: Arc<AtomicU64>,
+    channels: u32,
+}
+
+impl NullSink {
+    pub fn new() -> NullSink {
+        NullSink::default()
+    }
+
+    /// A counter of the frames played so far, usable while the sink is in use.
+    pub fn frame_counter(&self) -> Arc<AtomicU64> {
+        self.frames.clone()
+    }
+}
+
+impl AudioSink for NullSink {
+    fn start(&mut self, format: AudioFormat) -> bool {
+        self.channels = format.channels;
+        true
+    }
+
+    fn play(&mut self, samples: &[i16], _pts: i64) {
+        let frames = samples.len() as u64 / self.channels.max(1) as u64;
+        self.frames.fetch_add(frames, Ordering::Relaxed);
+    }
+}
+
+/// Peak and RMS of one channel over the latest buffer, linear from 0 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChannelLevel {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+impl ChannelLevel {
+    /// The peak in dBFS, `-inf` for silence.
+    pub fn peak_db(&self) -> f32 {
+        20.0 * self.peak.log10()
+    }
+
+    pub fn rms_db(&self) -> f32 {
+        20.0 * self.rms.log10()
+    }
+}
+
+/// Read side of a `LevelMeter`, cheap to clone and to read from the UI thread.
+#[derive(Clone, Default)]
+pub struct AudioLevels(Arc<Mutex<Vec<ChannelLevel>>>);
+
+impl AudioLevels {
+    /// One level per channel, empty while no audio plays.
+    pub fn get(&self) -> Vec<ChannelLevel> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn set(&self, levels: Vec<ChannelLevel>) {
+        *self.0.lock().unwrap() = levels;
+    }
+}
+
+/// Measures the level of every channel and discards the audio.
+#[derive(Default)]
+pub struct LevelMeter {
+    levels: AudioLevels,
+    channels: usize,
+}
+
+impl LevelMeter {
+    pub fn new() -> LevelMeter {
+        LevelMeter::default()
+    }
+
+    pub fn levels(&self) -> AudioLevels {
+        self.levels.clone()
+    }
+}
+
+impl AudioSink for LevelMeter {
+    fn start(&mut self, format: AudioFormat) -> bool {
+        self.channels = format.channels as usize;
+        self.channels > 0
+    }
+
+    fn play(&mut self, samples: &[i16], _pts: i64) {
+        if self.channels == 0 {
+            return;
+        }
+        let mut peaks = vec![0.0f32; self.channels];
+        let mut squares = vec![0.0f64; self.channels];
+        let mut frames = 0;
+        for frame in samples.chunks_exact(self.channels) {
+            for (channel, sample) in frame.iter().enumerate() {
+                let value = *sample as f32 / 32768.0;
+                peaks[channel] = peaks[channel].max(value.abs());
+                squares[channel] += (value * value) as f64;
+            }
+            frames += 1;
+        }
+        if frames == 0 {
+            return;
+        }
+        let levels = peaks
+            .into_iter()
+            .zip(squares)
+            .map(|(peak, square)| ChannelLevel {
+                peak,
+                rms: (square / frames as f64).sqrt() as f32,
+            })
+            .collect();
+        self.levels.set(levels);
+    }
+
+    fn stop(&mut self) {
+        self.levels.set(Vec::new());
+    }
+}
+
+/// Writes the audio to a 16 bit PCM WAV file, all media played with the sink end up
+/// in the same file. The sizes in the header are updated whenever a stream ends and
+/// when the sink is dropped.
+pub struct WavWriter {
+    file: BufWriter<File>,
+    format: Option<AudioFormat>,
+    /// Bytes of sample data written so far.
+    data_len: u32,
+    /// Set after a write failed, the rest of the audio is dropped.
+    failed: bool,
+}
+
+impl WavWriter {
+    /// Creates or truncates the file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<WavWriter> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| NeovideoError::Io {
+            path: path.to_string_lossy().into_owned(),
+            reason: e.to_string(),
+        })?;
+        Ok(WavWriter {
+            file: BufWriter::new(file),
+            format: None,
+            data_len: 0,
+            failed: false,
+        })
+    }
+
+    fn write_header(&mut self, format: AudioFormat) -> std::io::Result<()> {
+        let block_align = format.channels * 2;
+        let mut header = Vec::with_capacity(44);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(36 + self.data_len).to_le_bytes());
+        header.extend_from_slice(b"WAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        // integer PCM
+        header.extend_from_slice(&1u16.to_le_bytes());
+        header.extend_from_slice(&(format.channels as u16).to_le_bytes());
+        header.extend_from_slice(&format.rate.to_le_bytes());
+        header.extend_from_slice(&(format.rate * block_align).to_le_bytes());
+        header.extend_from_slice(&(block_align as u16).to_le_bytes());
+        header.extend_from_slice(&16u16.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&self.data_len.to_le_bytes());
+        self.file.write_all(&header)
+    }
+
+    /// Rewrites the header with the current sizes and flushes the file.
+    fn finish(&mut self) -> std::io::Result<()> {
+        if let Some(format) = self.format {
+            self.file.seek(SeekFrom::Start(0))?;
+            self.write_header(format)?;
+            self.file.seek(SeekFrom::End(0))?;
+        }
+        self.file.flush()
+    }
+}
+
+impl AudioSink for WavWriter {
+    /// Accepts any format for the first stream, later streams must match it as a WAV
+    /// file has a single format.
+    fn start(&mut self, format: AudioFormat) -> bool {
+        match self.format {
+            Some(current) => current == format,
+            None => {
+                self.format = Some(format);
+                self.failed = self.write_header(format).is_err();
+                true
+            }
+        }
+    }
+
+    fn play(&mut self, samples: &[i16], _pts: i64) {
+        // the data chunk size is a u32
+        let len = samples.len() as u64 * 2;
+        if self.failed || self.data_len as u64 + len > (u32::MAX - 36) as u64 {
+            return;
+        }
+        let mut bytes = Vec::with_capacity(len as usize);
+        for sample in samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        match self.file.write_all(&bytes) {
+            Ok(()) => self.data_len += len as u32,
+            Err(_) => self.failed = true,
+        }
+    }
+
+    fn stop(&mut self) {
+        self.failed |= self.finish().is_err();
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// A sink shared between `VLCVideo` and the callbacks of the players it is attached
+/// to, the player keeps a clone of the `Arc` until it is stopped.
+pub(crate) struct SinkSlot {
+    sink: Mutex<Box<dyn AudioSink>>,
+    /// Channels of the running stream, `play` only gets a frame count.
+    channels: AtomicU32,
+}
+
+impl SinkSlot {
+    pub(crate) fn new(sink: Box<dyn AudioSink>) -> Arc<SinkSlot> {
+        Arc::new(SinkSlot {
+            sink: Mutex::new(sink),
+            channels: AtomicU32::new(0),
+        })
+    }
+
+    /// Routes the audio of `player` into the sink, the player keeps `slot` alive. Must be
+    /// called before the player starts.
+    pub(crate) fn attach(slot: &Arc<SinkSlot>, player: &MediaPlayer) {
+        player.keep_alive(slot.clone());
+        let opaque = Arc::as_ptr(slot) as *mut c_void;
+        unsafe {
+            libvlc_audio_set_callbacks(
+                player.raw(),
+                Some(SinkSlot::play),
+                Some(SinkSlot::pause),
+                Some(SinkSlot::resume),
+                Some(SinkSlot::flush),
+                Some(SinkSlot::drain),
+                opaque,
+            );
+            libvlc_audio_set_format_callbacks(
+                player.raw(),
+                Some(SinkSlot::setup),
+                Some(SinkSlot::cleanup),
+            );
+        }
+    }
+
+    /// Runs `f` on the sink, `None` if it panicked. Every callback goes through here,
+    /// a sink left poisoned by a panic is not touched again.
+    fn with_sink<R>(&self, f: impl FnOnce(&mut dyn AudioSink) -> R) -> Option<R> {
+        let mut sink = self.sink.lock().ok()?;
+        ffi_guard(None, || Some(f(sink.as_mut())))
+    }
+
+    unsafe extern "C" fn setup(
+        opaque: *mut *mut c_void,
+        format: *mut c_char,
+        rate: *mut c_uint,
+        channels: *mut c_uint,
+    ) -> c_int {
+        let that = &*(*opaque as *const SinkSlot);
+        // libvlc converts to the sample format asked for here
+        std::ptr::copy_nonoverlapping(b"S16N".as_ptr() as *const c_char, format, 4);
+        let audio_format = AudioFormat {
+            rate: *rate,
+            channels: *channels,
+        };
+        that.channels.store(*channels, Ordering::Relaxed);
+        match that.with_sink(|sink| sink.start(audio_format)) {
+            Some(true) => 0,
+            _ => -1,
+        }
+    }
+
+    unsafe extern "C" fn cleanup(opaque: *mut c_void) {
+        let that = &*(opaque as *const SinkSlot);
+        that.with_sink(|sink| sink.stop());
+    }
+
+    unsafe extern "C" fn play(
+        opaque: *mut c_void,
+        samples: *const c_void,
+        count: c_uint,
+        pts: i64,
+    ) {
+        let that = &*(opaque as *const SinkSlot);
+        let channels = that.channels.load(Ordering::Relaxed);
+        if samples.is_null() || channels == 0 {
+            return;
+        }
+        let samples =
+            slice::from_raw_parts(samples as *const i16, count as usize * channels as usize);
+        that.with_sink(|sink| sink.play(samples, pts));
+    }
+
+    unsafe extern "C" fn pause(opaque: *mut c_void, pts: i64) {
+        let that = &*(opaque as *const SinkSlot);
+        that.with_sink(|sink| sink.pause(pts));
+    }
+
+    unsafe extern "C" fn resume(opaque: *mut c_void, pts: i64) {
+        let that = &*(opaque as *const SinkSlot);
+        that.with_sink(|sink| sink.resume(pts));
+    }
+
+    unsafe extern "C" fn flush(opaque: *mut c_void, pts: i64) {
+        let that = &*(opaque as *const SinkSlot);
+        that.with_sink(|sink| sink.flush(pts));
+    }
+
+    unsafe extern "C" fn drain(opaque: *mut c_void) {
+        let that = &*(opaque as *const SinkSlot);
+        that.with_sink(|sink| sink.drain());
+    }
+}
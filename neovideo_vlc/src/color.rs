@@ -0,0 +1,411 @@
+use super::vlc::{
+    libvlc_video_color_primaries_t, libvlc_video_color_space_t, libvlc_video_render_cfg_t,
+    libvlc_video_transfer_func_t,
+};
+
+/// Luminance of SDR reference white in nits, after ITU-R BT.2408. Linear light is
+/// relative to it, 1.0 being SDR white.
+pub const SDR_WHITE: f32 = 203.0;
+/// Peak luminance assumed for HDR sources in nits, libvlc does not pass on the
+/// mastering metadata.
+pub const HDR_PEAK: f32 = 1000.0;
+
+// SMPTE ST 2084
+const PQ_M1: f32 = 2610.0 / 16384.0;
+const PQ_M2: f32 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f32 = 3424.0 / 4096.0;
+const PQ_C2: f32 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+/// Chromaticities of the red, green and blue primaries of a video.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Primaries {
+    /// SMPTE C, 525 line standard definition.
+    Bt601_525,
+    /// EBU, 625 line standard definition.
+    Bt601_625,
+    #[default]
+    Bt709,
+    /// The wide gamut of UHD and HDR video.
+    Bt2020,
+    DciP3,
+    Bt470M,
+}
+
+impl Primaries {
+    fn from_raw(raw: u32) -> Option<Primaries> {
+        match raw {
+            1 => Some(Primaries::Bt601_525),
+            2 => Some(Primaries::Bt601_625),
+            3 => Some(Primaries::Bt709),
+            4 => Some(Primaries::Bt2020),
+            5 => Some(Primaries::DciP3),
+            6 => Some(Primaries::Bt470M),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> libvlc_video_color_primaries_t {
+        use libvlc_video_color_primaries_t::*;
+        match self {
+            Primaries::Bt601_525 => libvlc_video_primaries_BT601_525,
+            Primaries::Bt601_625 => libvlc_video_primaries_BT601_625,
+            Primaries::Bt709 => libvlc_video_primaries_BT709,
+            Primaries::Bt2020 => libvlc_video_primaries_BT2020,
+            Primaries::DciP3 => libvlc_video_primaries_DCI_P3,
+            Primaries::Bt470M => libvlc_video_primaries_BT470_M,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Primaries::Bt601_525 => "BT.601 (525)",
+            Primaries::Bt601_625 => "BT.601 (625)",
+            Primaries::Bt709 => "BT.709",
+            Primaries::Bt2020 => "BT.2020",
+            Primaries::DciP3 => "DCI-P3",
+            Primaries::Bt470M => "BT.470 M",
+        }
+    }
+
+    /// CIE xy of red, green and blue. Every gamut is taken with a D65 white, as on the
+    /// displays the video was mastered on.
+    fn chromaticities(self) -> [[f64; 2]; 3] {
+        match self {
+            Primaries::Bt601_525 => [[0.630, 0.340], [0.310, 0.595], [0.155, 0.070]],
+            Primaries::Bt601_625 | Primaries::Bt709 => {
+                [[0.640, 0.330], [0.300, 0.600], [0.150, 0.060]]
+            }
+            Primaries::Bt2020 => [[0.708, 0.292], [0.170, 0.797], [0.131, 0.046]],
+            Primaries::DciP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060]],
+            Primaries::Bt470M => [[0.670, 0.330], [0.210, 0.710], [0.140, 0.080]],
+        }
+    }
+
+    /// Linear RGB in these primaries to CIE XYZ.
+    fn to_xyz(self) -> [[f64; 3]; 3] {
+        const WHITE: [f64; 3] = [0.3127 / 0.3290, 1.0, (1.0 - 0.3127 - 0.3290) / 0.3290];
+        let columns = self
+            .chromaticities()
+            .map(|[x, y]| [x / y, 1.0, (1.0 - x - y) / y]);
+        let primaries = transpose(columns);
+        // scales the primaries so that they add up to white
+        let scale = multiply_vector(&invert(&primaries), WHITE);
+        primaries.map(|row| [row[0] * scale[0], row[1] * scale[1], row[2] * scale[2]])
+    }
+
+    /// The row major matrix taking linear RGB in these primaries to linear RGB in
+    /// `target`. Colours outside `target` come out negative or above 1.
+    pub fn conversion_to(self, target: Primaries) -> [[f32; 3]; 3] {
+        let matrix = multiply(&invert(&target.to_xyz()), &self.to_xyz());
+        matrix.map(|row| row.map(|value| value as f32))
+    }
+}
+
+/// Transfer function of a video, how its code values relate to light.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transfer {
+    Linear,
+    #[default]
+    Srgb,
+    Bt470Bg,
+    Bt470M,
+    Bt709,
+    /// SMPTE ST 2084, the perceptual quantizer of HDR10.
+    Pq,
+    Smpte240,
+    /// Hybrid log-gamma, ARIB STD-B67.
+    Hlg,
+}
+
+impl Transfer {
+    fn from_raw(raw: u32) -> Option<Transfer> {
+        match raw {
+            1 => Some(Transfer::Linear),
+            2 => Some(Transfer::Srgb),
+            3 => Some(Transfer::Bt470Bg),
+            4 => Some(Transfer::Bt470M),
+            5 => Some(Transfer::Bt709),
+            6 => Some(Transfer::Pq),
+            7 => Some(Transfer::Smpte240),
+            8 => Some(Transfer::Hlg),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> libvlc_video_transfer_func_t {
+        use libvlc_video_transfer_func_t::*;
+        match self {
+            Transfer::Linear => libvlc_video_transfer_func_LINEAR,
+            Transfer::Srgb => libvlc_video_transfer_func_SRGB,
+            Transfer::Bt470Bg => libvlc_video_transfer_func_BT470_BG,
+            Transfer::Bt470M => libvlc_video_transfer_func_BT470_M,
+            Transfer::Bt709 => libvlc_video_transfer_func_BT709,
+            Transfer::Pq => libvlc_video_transfer_func_PQ,
+            Transfer::Smpte240 => libvlc_video_transfer_func_SMPTE_240,
+            Transfer::Hlg => libvlc_video_transfer_func_HLG,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Transfer::Linear => "Linear",
+            Transfer::Srgb => "sRGB",
+            Transfer::Bt470Bg => "BT.470 BG",
+            Transfer::Bt470M => "BT.470 M",
+            Transfer::Bt709 => "BT.709",
+            Transfer::Pq => "PQ",
+            Transfer::Smpte240 => "SMPTE 240M",
+            Transfer::Hlg => "HLG",
+        }
+    }
+
+    /// Whether the code values reach beyond SDR reference white.
+    pub fn is_hdr(self) -> bool {
+        matches!(self, Transfer::Pq | Transfer::Hlg)
+    }
+
+    /// Linear light of the code values `rgb`, relative to SDR white. HLG is displayed
+    /// as on a display of `HDR_PEAK`, SDR transfers as on a gamma 2.4 display. The
+    /// shader of `TextureRender` does the same.
+    pub fn to_linear(self, rgb: [f32; 3]) -> [f32; 3] {
+        match self {
+            Transfer::Pq => rgb.map(|e| {
+                let p = e.clamp(0.0, 1.0).powf(1.0 / PQ_M2);
+                let y = ((p - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * p)).powf(1.0 / PQ_M1);
+                y * 10000.0 / SDR_WHITE
+            }),
+            Transfer::Hlg => {
+                const A: f32 = 0.17883277;
+                const B: f32 = 0.28466892;
+                const C: f32 = 0.5599107;
+                let scene = rgb.map(|e| {
+                    if e < 0.5 {
+                        e * e / 3.0
+                    } else {
+                        (((e - C) / A).exp() + B) / 12.0
+                    }
+                });
+                // the reference OOTF
+                let luma = 0.2627 * scene[0] + 0.6780 * scene[1] + 0.0593 * scene[2];
+                let gain = luma.max(1e-6).powf(0.2) * HDR_PEAK / SDR_WHITE;
+                scene.map(|c| c * gain)
+            }
+            Transfer::Linear => rgb,
+            _ => rgb.map(|e| e.max(0.0).powf(2.4)),
+        }
+    }
+}
+
+/// The PQ code value of linear light `l`, relative to SDR white.
+pub fn pq_from_linear(l: f32) -> f32 {
+    let y = (l * SDR_WHITE / 10000.0).max(0.0).powf(PQ_M1);
+    ((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y)).powf(PQ_M2)
+}
+
+/// Matrix between the RGB and YCbCr of a video.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    Bt601,
+    #[default]
+    Bt709,
+    Bt2020,
+}
+
+impl ColorSpace {
+    fn from_raw(raw: u32) -> Option<ColorSpace> {
+        match raw {
+            1 => Some(ColorSpace::Bt601),
+            2 => Some(ColorSpace::Bt709),
+            3 => Some(ColorSpace::Bt2020),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> libvlc_video_color_space_t {
+        use libvlc_video_color_space_t::*;
+        match self {
+            ColorSpace::Bt601 => libvlc_video_colorspace_BT601,
+            ColorSpace::Bt709 => libvlc_video_colorspace_BT709,
+            ColorSpace::Bt2020 => libvlc_video_colorspace_BT2020,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorSpace::Bt601 => "BT.601",
+            ColorSpace::Bt709 => "BT.709",
+            ColorSpace::Bt2020 => "BT.2020",
+        }
+    }
+}
+
+/// How the decoded video encodes colour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColorInfo {
+    pub primaries: Primaries,
+    pub transfer: Transfer,
+    pub space: ColorSpace,
+    /// Whether the code values use the full range instead of the studio range.
+    pub full_range: bool,
+    /// Bits per channel.
+    pub bit_depth: u32,
+}
+
+impl Default for ColorInfo {
+    fn default() -> ColorInfo {
+        ColorInfo {
+            primaries: Primaries::default(),
+            transfer: Transfer::default(),
+            space: ColorSpace::default(),
+            full_range: false,
+            bit_depth: 8,
+        }
+    }
+}
+
+impl ColorInfo {
+    /// Reads the format libvlc describes to the resize callback, values unknown to this
+    /// crate fall back to the defaults.
+    ///
+    /// # Safety
+    ///
+    /// `cfg` must point to a valid `libvlc_video_render_cfg_t`.
+    pub(crate) unsafe fn from_render_cfg(cfg: *const libvlc_video_render_cfg_t) -> ColorInfo {
+        // libvlc may hand over values added after these bindings, which would not be
+        // valid Rust enums, so they are read as plain integers
+        let primaries = std::ptr::addr_of!((*cfg).primaries) as *const u32;
+        let transfer = std::ptr::addr_of!((*cfg).transfer) as *const u32;
+        let space = std::ptr::addr_of!((*cfg).colorspace) as *const u32;
+        ColorInfo {
+            primaries: Primaries::from_raw(primaries.read()).unwrap_or_default(),
+            transfer: Transfer::from_raw(transfer.read()).unwrap_or_default(),
+            space: ColorSpace::from_raw(space.read()).unwrap_or_default(),
+            full_range: (*cfg).full_range,
+            bit_depth: (*cfg).bitdepth,
+        }
+    }
+
+    pub fn is_hdr(&self) -> bool {
+        self.transfer.is_hdr()
+    }
+
+    /// Whether showing the video on an sRGB display takes more than sampling it, that
+    /// is for HDR and wide gamut video.
+    pub fn needs_conversion(&self) -> bool {
+        self.is_hdr() || matches!(self.primaries, Primaries::Bt2020 | Primaries::DciP3)
+    }
+
+    /// A short description, e.g. "HDR10 (PQ, BT.2020)".
+    pub fn describe(&self) -> String {
+        let kind = match self.transfer {
+            Transfer::Pq => "HDR10",
+            Transfer::Hlg => "HLG",
+            _ if self.needs_conversion() => "Wide gamut SDR",
+            _ => "SDR",
+        };
+        format!(
+            "{} ({}, {}, {} bit)",
+            kind,
+            self.transfer.name(),
+            self.primaries.name(),
+            self.bit_depth
+        )
+    }
+}
+
+/// Operator compressing HDR highlights into the range of an SDR display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToneMapping {
+    /// Extended Reinhard, rolls off smoothly from black.
+    Reinhard,
+    /// The filmic curve of John Hable, with a toe in the shadows.
+    Hable,
+    /// The EETF of ITU-R BT.2390, leaves the midtones alone.
+    #[default]
+    Bt2390,
+}
+
+impl ToneMapping {
+    pub const ALL: [ToneMapping; 3] = [
+        ToneMapping::Reinhard,
+        ToneMapping::Hable,
+        ToneMapping::Bt2390,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ToneMapping::Reinhard => "Reinhard",
+            ToneMapping::Hable => "Hable",
+            ToneMapping::Bt2390 => "BT.2390",
+        }
+    }
+
+    /// Maps linear light `rgb` with highlights up to `peak` into SDR range, both
+    /// relative to SDR white. The shader of `TextureRender` does the same.
+    pub fn apply(self, rgb: [f32; 3], peak: f32) -> [f32; 3] {
+        if peak <= 1.0 {
+            return rgb;
+        }
+        match self {
+            // extended so that the peak lands on white
+            ToneMapping::Reinhard => rgb.map(|x| x * (1.0 + x / (peak * peak)) / (1.0 + x)),
+            ToneMapping::Hable => {
+                fn curve(x: f32) -> f32 {
+                    const A: f32 = 0.15;
+                    const B: f32 = 0.50;
+                    const C: f32 = 0.10;
+                    const D: f32 = 0.20;
+                    const E: f32 = 0.02;
+                    const F: f32 = 0.30;
+                    (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F
+                }
+                rgb.map(|x| curve(x) / curve(peak))
+            }
+            ToneMapping::Bt2390 => {
+                // the EETF on the brightest channel, in PQ space normalized to the peak
+                let m = rgb[0].max(rgb[1]).max(rgb[2]);
+                if m <= 0.0 {
+                    return rgb;
+                }
+                let source_peak = pq_from_linear(peak);
+                let e1 = pq_from_linear(m) / source_peak;
+                let max_lum = pq_from_linear(1.0) / source_peak;
+                let ks = 1.5 * max_lum - 0.5;
+                let mut e2 = e1;
+                if e1 > ks {
+                    let t = (e1 - ks) / (1.0 - ks);
+                    let (t2, t3) = (t * t, t * t * t);
+                    e2 = (2.0 * t3 - 3.0 * t2 + 1.0) * ks
+                        + (t3 - 2.0 * t2 + t) * (1.0 - ks)
+                        + (-2.0 * t3 + 3.0 * t2) * max_lum;
+                }
+                let mapped = Transfer::Pq.to_linear([e2 * source_peak; 3])[0];
+                rgb.map(|c| c * mapped / m)
+            }
+        }
+    }
+}
+
+fn transpose(m: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    [0, 1, 2].map(|row| [0, 1, 2].map(|col| m[col][row]))
+}
+
+fn multiply(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    [0, 1, 2].map(|row| [0, 1, 2].map(|col| (0..3).map(|k| a[row][k] * b[k][col]).sum()))
+}
+
+fn multiply_vector(m: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn invert(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    // the adjugate over the determinant, the matrices of real primaries are regular
+    let cofactor = |row: usize, col: usize| {
+        let (r0, r1) = ((row + 1) % 3, (row + 2) % 3);
+        let (c0, c1) = ((col + 1) % 3, (col + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let det = (0..3).map(|col| m[0][col] * cofactor(0, col)).sum::<f64>();
+    [0, 1, 2].map(|row| [0, 1, 2].map(|col| cofactor(col, row) / det))
+}
@@ -0,0 +1,142 @@
+use super::error::{NeovideoError, Result};
+use super::tools::from_cstr_ref;
+use super::vlc::{
+    libvlc_audio_equalizer_get_amp_at_index, libvlc_audio_equalizer_get_band_count,
+    libvlc_audio_equalizer_get_band_frequency, libvlc_audio_equalizer_get_preamp,
+    libvlc_audio_equalizer_get_preset_count, libvlc_audio_equalizer_get_preset_name,
+    libvlc_audio_equalizer_new, libvlc_audio_equalizer_new_from_preset,
+    libvlc_audio_equalizer_release, libvlc_audio_equalizer_set_amp_at_index,
+    libvlc_audio_equalizer_set_preamp, libvlc_equalizer_t,
+};
+
+/// Audio equalizer settings, applied with `MediaPlayer::set_equalizer`.
+///
+/// libvlc equalizers are plain settings objects without reference counting, so
+/// `try_clone` copies the values into a new equalizer.
+pub struct Equalizer {
+    ptr: *mut libvlc_equalizer_t,
+}
+
+// Not Sync: libvlc does not synchronize access to equalizer settings.
+unsafe impl Send for Equalizer {}
+
+impl Equalizer {
+    /// Creates a flat equalizer.
+    pub fn new() -> Result<Equalizer> {
+        unsafe { Equalizer::from_ptr(libvlc_audio_equalizer_new()) }
+    }
+
+    /// Creates an equalizer with the settings of preset `index`, see `preset_name`.
+    pub fn from_preset(index: u32) -> Result<Equalizer> {
+        if index >= Equalizer::preset_count() {
+            return Err(NeovideoError::InvalidArgument(format!(
+                "no equalizer preset {}",
+                index
+            )));
+        }
+        unsafe { Equalizer::from_ptr(libvlc_audio_equalizer_new_from_preset(index)) }
+    }
+
+    /// Creates an equalizer with the given pre-amplification and band amplifications in
+    /// dB, bands missing from `amps` stay flat.
+    pub fn with_amps(preamp: f32, amps: &[f32]) -> Result<Equalizer> {
+        let mut equalizer = Equalizer::new()?;
+        equalizer.set_preamp(preamp)?;
+        for (band, amp) in (0..Equalizer::band_count()).zip(amps) {
+            equalizer.set_amp_at_index(*amp, band)?;
+        }
+        Ok(equalizer)
+    }
+
+    unsafe fn from_ptr(ptr: *mut libvlc_equalizer_t) -> Result<Equalizer> {
+        if ptr.is_null() {
+            Err(NeovideoError::vlc("could not create the equalizer"))
+        } else {
+            Ok(Equalizer { ptr })
+        }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> *mut libvlc_equalizer_t {
+        self.ptr
+    }
+
+    pub fn preset_count() -> u32 {
+        unsafe { libvlc_audio_equalizer_get_preset_count() }
+    }
+
+    pub fn preset_name(index: u32) -> Option<String> {
+        unsafe { from_cstr_ref(libvlc_audio_equalizer_get_preset_name(index)) }
+    }
+
+    pub fn band_count() -> u32 {
+        unsafe { libvlc_audio_equalizer_get_band_count() }
+    }
+
+    /// Band center frequency in Hz.
+    pub fn band_frequency(index: u32) -> Option<f32> {
+        let frequency = unsafe { libvlc_audio_equalizer_get_band_frequency(index) };
+        if frequency < 0.0 {
+            None
+        } else {
+            Some(frequency)
+        }
+    }
+
+    /// Pre-amplification in dB.
+    pub fn preamp(&self) -> f32 {
+        unsafe { libvlc_audio_equalizer_get_preamp(self.ptr) }
+    }
+
+    /// Sets the pre-amplification in dB. Values outside of -20..=20 dB are clamped, not
+    /// rejected.
+    pub fn set_preamp(&mut self, preamp: f32) -> Result<()> {
+        if unsafe { libvlc_audio_equalizer_set_preamp(self.ptr, preamp) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not change the preamp"))
+        }
+    }
+
+    /// Amplification of `band` in dB.
+    pub fn amp_at_index(&self, band: u32) -> f32 {
+        unsafe { libvlc_audio_equalizer_get_amp_at_index(self.ptr, band) }
+    }
+
+    /// Amplification of every band in dB, lowest frequency first.
+    pub fn amps(&self) -> Vec<f32> {
+        (0..Equalizer::band_count())
+            .map(|band| self.amp_at_index(band))
+            .collect()
+    }
+
+    /// Sets the amplification of `band` in dB, clamped like the preamp.
+    pub fn set_amp_at_index(&mut self, amp: f32, band: u32) -> Result<()> {
+        if band >= Equalizer::band_count() {
+            return Err(NeovideoError::InvalidArgument(format!(
+                "no equalizer band {}",
+                band
+            )));
+        }
+        if unsafe { libvlc_audio_equalizer_set_amp_at_index(self.ptr, amp, band) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc(
+                "could not change the band amplification",
+            ))
+        }
+    }
+
+    /// A new equalizer with the same settings.
+    pub fn try_clone(&self) -> Result<Equalizer> {
+        Equalizer::with_amps(self.preamp(), &self.amps())
+    }
+}
+
+impl Drop for Equalizer {
+    fn drop(&mut self) {
+        unsafe {
+            libvlc_audio_equalizer_release(self.ptr);
+        }
+    }
+}
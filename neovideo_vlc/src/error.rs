@@ -0,0 +1,96 @@
+use std::fmt;
+
+use super::tools::from_cstr_ref;
+use super::vlc::{libvlc_clearerr, libvlc_errmsg};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NeovideoError {
+    /// The MRL or path cannot be handed to libvlc, e.g. it is not valid UTF-8.
+    InvalidMrl(String),
+    InstanceCreation(Option<String>),
+    MediaCreation {
+        mrl: String,
+        reason: Option<String>,
+    },
+    PlayerCreation(Option<String>),
+    /// Creating or binding the OpenGL context used by the video output failed.
+    GlSetup(String),
+    /// Any other failure reported by libvlc.
+    Vlc(String),
+    /// The call needs the player of a media, none was played yet.
+    NothingPlaying,
+    /// An argument is outside of what the call accepts, checked before libvlc is asked.
+    InvalidArgument(String),
+    /// Reading or writing a file failed.
+    Io {
+        path: String,
+        reason: String,
+    },
+    /// A playlist file is malformed, `line` counts from 1.
+    PlaylistParse {
+        line: usize,
+        reason: String,
+    },
+    /// A subtitle file is malformed, `line` counts from 1.
+    SubtitleParse {
+        line: usize,
+        reason: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, NeovideoError>;
+
+impl NeovideoError {
+    /// Takes the last error message libvlc reported on this thread, if any.
+    pub fn last_vlc_error() -> Option<String> {
+        unsafe {
+            let msg = from_cstr_ref(libvlc_errmsg());
+            libvlc_clearerr();
+            msg
+        }
+    }
+
+    /// Builds a `Vlc` error from `libvlc_errmsg`, falling back to `context`.
+    pub(crate) fn vlc(context: &str) -> NeovideoError {
+        NeovideoError::Vlc(NeovideoError::last_vlc_error().unwrap_or_else(|| context.to_owned()))
+    }
+}
+
+impl fmt::Display for NeovideoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn reason(f: &mut fmt::Formatter<'_>, reason: &Option<String>) -> fmt::Result {
+            match reason {
+                Some(reason) => write!(f, ": {}", reason),
+                None => Ok(()),
+            }
+        }
+        match self {
+            NeovideoError::InvalidMrl(mrl) => write!(f, "invalid media location '{}'", mrl),
+            NeovideoError::InstanceCreation(r) => {
+                write!(f, "failed to create libvlc instance")?;
+                reason(f, r)
+            }
+            NeovideoError::MediaCreation { mrl, reason: r } => {
+                write!(f, "failed to open '{}'", mrl)?;
+                reason(f, r)
+            }
+            NeovideoError::PlayerCreation(r) => {
+                write!(f, "failed to create media player")?;
+                reason(f, r)
+            }
+            NeovideoError::GlSetup(msg) => write!(f, "OpenGL setup failed: {}", msg),
+            NeovideoError::Vlc(msg) => write!(f, "libvlc error: {}", msg),
+            NeovideoError::NothingPlaying => write!(f, "nothing is playing"),
+            NeovideoError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+            NeovideoError::Io { path, reason } => write!(f, "'{}': {}", path, reason),
+            NeovideoError::PlaylistParse { line, reason } => {
+                write!(f, "invalid playlist at line {}: {}", line, reason)
+            }
+            NeovideoError::SubtitleParse { line, reason } => {
+                write!(f, "invalid subtitles at line {}: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NeovideoError {}
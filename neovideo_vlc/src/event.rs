@@ -0,0 +1,294 @@
+use std::any::Any;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use libc::{c_int, c_void};
+use winit::event_loop::EventLoopProxy;
+
+use super::error::{NeovideoError, Result};
+use super::tools::{ffi_guard, from_cstr_ref};
+use super::vlc::{
+    libvlc_event_attach, libvlc_event_detach, libvlc_event_manager_t, libvlc_event_t,
+    libvlc_event_type_t, EventType, Meta, State,
+};
+
+/// A libvlc event with its payload decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    MediaMetaChanged(Meta),
+    MediaSubItemAdded,
+    /// New duration in milliseconds.
+    MediaDurationChanged(i64),
+    MediaParsedChanged(i32),
+    MediaFreed,
+    MediaStateChanged(State),
+    MediaSubItemTreeAdded,
+
+    MediaChanged,
+    NothingSpecial,
+    Opening,
+    /// Buffer fill level in percent.
+    Buffering(f32),
+    Playing,
+    Paused,
+    Stopped,
+    Forward,
+    Backward,
+    EndReached,
+    EncounteredError,
+    /// Current playback time in milliseconds.
+    TimeChanged(i64),
+    /// Current position in the range `0.0..=1.0`.
+    PositionChanged(f32),
+    SeekableChanged(bool),
+    PausableChanged(bool),
+    TitleChanged(i32),
+    SnapshotTaken(Option<String>),
+    /// New media length in milliseconds.
+    LengthChanged(i64),
+    /// Number of video outputs.
+    Vout(i32),
+    ScrambledChanged(bool),
+
+    MediaListItemAdded(usize),
+    MediaListWillAddItem(usize),
+    MediaListItemDeleted(usize),
+    MediaListWillDeleteItem(usize),
+
+    /// An event of a type, or with a payload, unknown to this crate, holding the raw
+    /// event type.
+    Unknown(i32),
+}
+
+/// Every event a media player emits.
+pub const MEDIA_PLAYER_EVENTS: &[EventType] = &[
+    EventType::MediaPlayerMediaChanged,
+    EventType::MediaPlayerNothingSpecial,
+    EventType::MediaPlayerOpening,
+    EventType::MediaPlayerBuffering,
+    EventType::MediaPlayerPlaying,
+    EventType::MediaPlayerPaused,
+    EventType::MediaPlayerStopped,
+    EventType::MediaPlayerForward,
+    EventType::MediaPlayerBackward,
+    EventType::MediaPlayerEndReached,
+    EventType::MediaPlayerEncounteredError,
+    EventType::MediaPlayerTimeChanged,
+    EventType::MediaPlayerPositionChanged,
+    EventType::MediaPlayerSeekableChanged,
+    EventType::MediaPlayerPausableChanged,
+    EventType::MediaPlayerTitleChanged,
+    EventType::MediaPlayerSnapshotTaken,
+    EventType::MediaPlayerLengthChanged,
+    EventType::MediaPlayerVout,
+    EventType::MediaPlayerScrambledChanged,
+];
+
+/// Every event a media emits.
+pub const MEDIA_EVENTS: &[EventType] = &[
+    EventType::MediaMetaChanged,
+    EventType::MediaSubItemAdded,
+    EventType::MediaDurationChanged,
+    EventType::MediaParsedChanged,
+    EventType::MediaFreed,
+    EventType::MediaStateChanged,
+    EventType::MediaSubItemTreeAdded,
+];
+
+impl Event {
+    /// Decodes a raw event, `Event::Unknown` for event types and payload values without
+    /// a Rust counterpart.
+    ///
+    /// # Safety
+    /// `event` must be an event delivered by libvlc, its union must match its type.
+    pub unsafe fn from_raw(event: &libvlc_event_t) -> Event {
+        use EventType as E;
+
+        let u = &event.u;
+        let ty = event._type;
+        let is = |e: EventType| ty == e as c_int;
+        let unknown = Event::Unknown(ty);
+        if is(E::MediaMetaChanged) {
+            Meta::from_raw(u.media_meta_changed.meta_type).map_or(unknown, Event::MediaMetaChanged)
+        } else if is(E::MediaSubItemAdded) {
+            Event::MediaSubItemAdded
+        } else if is(E::MediaDurationChanged) {
+            Event::MediaDurationChanged(u.media_duration_changed.new_duration)
+        } else if is(E::MediaParsedChanged) {
+            Event::MediaParsedChanged(u.media_parsed_changed.new_status)
+        } else if is(E::MediaFreed) {
+            Event::MediaFreed
+        } else if is(E::MediaStateChanged) {
+            State::from_raw(u.media_state_changed.new_state)
+                .map_or(unknown, Event::MediaStateChanged)
+        } else if is(E::MediaSubItemTreeAdded) {
+            Event::MediaSubItemTreeAdded
+        } else if is(E::MediaPlayerMediaChanged) {
+            Event::MediaChanged
+        } else if is(E::MediaPlayerNothingSpecial) {
+            Event::NothingSpecial
+        } else if is(E::MediaPlayerOpening) {
+            Event::Opening
+        } else if is(E::MediaPlayerBuffering) {
+            Event::Buffering(u.media_player_buffering.new_cache)
+        } else if is(E::MediaPlayerPlaying) {
+            Event::Playing
+        } else if is(E::MediaPlayerPaused) {
+            Event::Paused
+        } else if is(E::MediaPlayerStopped) {
+            Event::Stopped
+        } else if is(E::MediaPlayerForward) {
+            Event::Forward
+        } else if is(E::MediaPlayerBackward) {
+            Event::Backward
+        } else if is(E::MediaPlayerEndReached) {
+            Event::EndReached
+        } else if is(E::MediaPlayerEncounteredError) {
+            Event::EncounteredError
+        } else if is(E::MediaPlayerTimeChanged) {
+            Event::TimeChanged(u.media_player_time_changed.new_time)
+        } else if is(E::MediaPlayerPositionChanged) {
+            Event::PositionChanged(u.media_player_position_changed.new_position)
+        } else if is(E::MediaPlayerSeekableChanged) {
+            Event::SeekableChanged(u.media_player_seekable_changed.new_seekable != 0)
+        } else if is(E::MediaPlayerPausableChanged) {
+            Event::PausableChanged(u.media_player_pausable_changed.new_pausable != 0)
+        } else if is(E::MediaPlayerTitleChanged) {
+            Event::TitleChanged(u.media_player_title_changed.new_titie)
+        } else if is(E::MediaPlayerSnapshotTaken) {
+            Event::SnapshotTaken(from_cstr_ref(u.media_player_snapshot_taken.psz_filename))
+        } else if is(E::MediaPlayerLengthChanged) {
+            Event::LengthChanged(u.media_player_length_changed.new_length)
+        } else if is(E::MediaPlayerVout) {
+            Event::Vout(u.media_player_vout.new_count)
+        } else if is(E::MediaPlayerScrambledChanged) {
+            Event::ScrambledChanged(u.media_player_scrambled_changed.new_scrambled != 0)
+        } else if is(E::MediaListItemAdded) {
+            Event::MediaListItemAdded(u.media_list_item_added.index as usize)
+        } else if is(E::MediaListWillAddItem) {
+            Event::MediaListWillAddItem(u.media_list_will_add_item.index as usize)
+        } else if is(E::MediaListItemDeleted) {
+            Event::MediaListItemDeleted(u.media_list_item_deleted.index as usize)
+        } else if is(E::MediaListWillDeleteItem) {
+            Event::MediaListWillDeleteItem(u.media_list_will_delete_item.index as usize)
+        } else {
+            unknown
+        }
+    }
+}
+
+type Callback = Box<dyn Fn(Event) + Send>;
+
+/// The event manager of a media, media player or media list.
+///
+/// It keeps its owner alive, as does every subscription made through it.
+pub struct EventManager {
+    ptr: *mut libvlc_event_manager_t,
+    owner: Arc<dyn Any + Send + Sync>,
+}
+
+unsafe impl Send for EventManager {}
+unsafe impl Sync for EventManager {}
+
+impl EventManager {
+    pub(crate) fn new<T: Any + Send + Sync>(
+        ptr: *mut libvlc_event_manager_t,
+        owner: T,
+    ) -> EventManager {
+        EventManager {
+            ptr,
+            owner: Arc::new(owner),
+        }
+    }
+
+    /// Calls `callback` on a libvlc thread for every event of the given types until the
+    /// returned subscription is dropped.
+    pub fn attach<F>(&self, types: &[EventType], callback: F) -> Result<Subscription>
+    where
+        F: Fn(Event) + Send + 'static,
+    {
+        let callback: Box<Callback> = Box::new(Box::new(callback));
+        let mut subscription = Subscription {
+            ptr: self.ptr,
+            types: Vec::with_capacity(types.len()),
+            data: Box::into_raw(callback),
+            _owner: self.owner.clone(),
+        };
+        for &ty in types {
+            let ret = unsafe {
+                libvlc_event_attach(
+                    self.ptr,
+                    ty as libvlc_event_type_t,
+                    dispatch,
+                    subscription.data as *mut c_void,
+                )
+            };
+            if ret != 0 {
+                // dropping the subscription detaches what was attached so far
+                return Err(NeovideoError::vlc("libvlc_event_attach failed"));
+            }
+            subscription.types.push(ty);
+        }
+        Ok(subscription)
+    }
+
+    /// Forwards events to `sender`, events are dropped once the receiver hangs up.
+    pub fn attach_sender(
+        &self,
+        types: &[EventType],
+        sender: Sender<Event>,
+    ) -> Result<Subscription> {
+        self.attach(types, move |event| {
+            let _ = sender.send(event);
+        })
+    }
+
+    /// Wakes the winit event loop with a user event built from each libvlc event.
+    pub fn attach_proxy<T, F>(
+        &self,
+        types: &[EventType],
+        proxy: EventLoopProxy<T>,
+        map: F,
+    ) -> Result<Subscription>
+    where
+        T: Send + 'static,
+        F: Fn(Event) -> T + Send + 'static,
+    {
+        self.attach(types, move |event| {
+            let _ = proxy.send_event(map(event));
+        })
+    }
+}
+
+/// An attached callback, detached when dropped.
+pub struct Subscription {
+    ptr: *mut libvlc_event_manager_t,
+    types: Vec<EventType>,
+    data: *mut Callback,
+    _owner: Arc<dyn Any + Send + Sync>,
+}
+
+unsafe impl Send for Subscription {}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        unsafe {
+            for &ty in &self.types {
+                libvlc_event_detach(
+                    self.ptr,
+                    ty as libvlc_event_type_t,
+                    dispatch,
+                    self.data as *mut c_void,
+                );
+            }
+            drop(Box::from_raw(self.data));
+        }
+    }
+}
+
+unsafe extern "C" fn dispatch(event: *const libvlc_event_t, data: *mut c_void) {
+    let callback = &*(data as *const Callback);
+    let event = Event::from_raw(&*event);
+    // a failing callback only loses its event
+    ffi_guard((), || callback(event));
+}
@@ -0,0 +1,96 @@
+use std::ffi::CString;
+
+use libc::{c_char, c_int};
+
+use super::audio_output::{AudioDevice, AudioOutput};
+use super::error::{NeovideoError, Result};
+use super::tools::to_cstr;
+use super::vlc::{
+    libvlc_audio_output_device_list_get, libvlc_audio_output_list_get, libvlc_instance_t,
+    libvlc_new, libvlc_release, libvlc_retain,
+};
+
+/// A libvlc instance. Cloning retains the underlying handle, dropping releases it.
+pub struct Instance {
+    ptr: *mut libvlc_instance_t,
+}
+
+// libvlc instances are reference counted and internally synchronized.
+unsafe impl Send for Instance {}
+unsafe impl Sync for Instance {}
+
+impl Instance {
+    pub fn new() -> Result<Instance> {
+        Instance::with_args::<&str>(&[])
+    }
+
+    /// Creates an instance with command line style arguments, e.g. `--no-audio`.
+    pub fn with_args<S: AsRef<str>>(args: &[S]) -> Result<Instance> {
+        let args = args
+            .iter()
+            .map(|arg| CString::new(arg.as_ref()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| {
+                NeovideoError::InstanceCreation(Some("argument contains a NUL byte".to_owned()))
+            })?;
+        let argv: Vec<*const c_char> = args.iter().map(|arg| arg.as_ptr()).collect();
+        let ptr = unsafe { libvlc_new(argv.len() as c_int, argv.as_ptr()) };
+        if ptr.is_null() {
+            Err(NeovideoError::InstanceCreation(
+                NeovideoError::last_vlc_error(),
+            ))
+        } else {
+            Ok(Instance { ptr })
+        }
+    }
+
+    /// Takes ownership of one reference to `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must be a valid, non-null instance whose reference is not released elsewhere.
+    pub unsafe fn from_raw(ptr: *mut libvlc_instance_t) -> Instance {
+        Instance { ptr }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> *mut libvlc_instance_t {
+        self.ptr
+    }
+
+    /// The audio output modules available to players of this instance.
+    pub fn audio_outputs(&self) -> Vec<AudioOutput> {
+        unsafe { AudioOutput::from_list(libvlc_audio_output_list_get(self.ptr)) }
+    }
+
+    /// The devices of the audio output module `output`, empty if the module cannot
+    /// enumerate them.
+    pub fn audio_devices(&self, output: &str) -> Vec<AudioDevice> {
+        let output = match to_cstr(output) {
+            Some(output) => output,
+            None => return Vec::new(),
+        };
+        unsafe {
+            AudioDevice::from_list(libvlc_audio_output_device_list_get(
+                self.ptr,
+                output.as_ptr(),
+            ))
+        }
+    }
+}
+
+impl Clone for Instance {
+    fn clone(&self) -> Instance {
+        unsafe {
+            libvlc_retain(self.ptr);
+        }
+        Instance { ptr: self.ptr }
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        unsafe {
+            libvlc_release(self.ptr);
+        }
+    }
+}
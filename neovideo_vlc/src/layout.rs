@@ -0,0 +1,113 @@
+/// How the video is scaled into the viewport.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScaleMode {
+    /// The whole picture is visible, with black bars where the aspect ratios differ.
+    #[default]
+    Fit,
+    /// The viewport is covered, cropping the picture where the aspect ratios differ.
+    Fill,
+    /// The picture covers the viewport exactly, ignoring its aspect ratio.
+    Stretch,
+    /// `Fit` scaled by the given factor, cropped to the viewport.
+    Zoom(f32),
+}
+
+/// The display aspect ratio of the video.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// The aspect ratio of the decoded picture.
+    #[default]
+    Source,
+    /// 4:3
+    Standard,
+    /// 16:9
+    Widescreen,
+    /// 2.35:1
+    Scope,
+}
+
+impl AspectRatio {
+    pub const ALL: [AspectRatio; 4] = [
+        AspectRatio::Source,
+        AspectRatio::Standard,
+        AspectRatio::Widescreen,
+        AspectRatio::Scope,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AspectRatio::Source => "Source",
+            AspectRatio::Standard => "4:3",
+            AspectRatio::Widescreen => "16:9",
+            AspectRatio::Scope => "2.35:1",
+        }
+    }
+
+    /// Width over height for a picture of `video_size` pixels.
+    pub fn value(self, video_size: (u32, u32)) -> Option<f32> {
+        match self {
+            AspectRatio::Source if video_size.0 == 0 || video_size.1 == 0 => None,
+            AspectRatio::Source => Some(video_size.0 as f32 / video_size.1 as f32),
+            AspectRatio::Standard => Some(4.0 / 3.0),
+            AspectRatio::Widescreen => Some(16.0 / 9.0),
+            AspectRatio::Scope => Some(2.35),
+        }
+    }
+}
+
+/// Where the video lands in the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VideoQuad {
+    /// `[left, top, right, bottom]` in viewport units, origin at the top left.
+    pub rect: [f32; 4],
+    /// The visible part of the picture as `[left, top, right, bottom]` in `0.0..=1.0`,
+    /// origin at the top left of the picture.
+    pub uv: [f32; 4],
+}
+
+impl VideoQuad {
+    /// Lays out a picture of `video_size` pixels in a viewport of `viewport` units,
+    /// `None` while either is empty.
+    pub fn new(
+        video_size: (u32, u32),
+        viewport: (f32, f32),
+        mode: ScaleMode,
+        aspect_ratio: AspectRatio,
+    ) -> Option<VideoQuad> {
+        let (vw, vh) = viewport;
+        if vw <= 0.0 || vh <= 0.0 {
+            return None;
+        }
+        let aspect = aspect_ratio.value(video_size)?;
+        let fit = if vw / vh > aspect {
+            (vh * aspect, vh)
+        } else {
+            (vw, vw / aspect)
+        };
+        let (width, height) = match mode {
+            ScaleMode::Fit => fit,
+            ScaleMode::Fill if vw / vh > aspect => (vw, vw / aspect),
+            ScaleMode::Fill => (vh * aspect, vh),
+            ScaleMode::Stretch => (vw, vh),
+            ScaleMode::Zoom(zoom) => (fit.0 * zoom.max(0.01), fit.1 * zoom.max(0.01)),
+        };
+
+        // centre the picture, cropping whatever falls outside the viewport
+        let (left, u0, u1) = crop(width, vw);
+        let (top, v0, v1) = crop(height, vh);
+        Some(VideoQuad {
+            rect: [left, top, vw - left, vh - top],
+            uv: [u0, v0, u1, v1],
+        })
+    }
+}
+
+/// Centres `size` in `extent`, returns the offset and the visible range of the picture.
+fn crop(size: f32, extent: f32) -> (f32, f32, f32) {
+    if size <= extent {
+        ((extent - size) / 2.0, 0.0, 1.0)
+    } else {
+        let visible = extent / size;
+        (0.0, (1.0 - visible) / 2.0, (1.0 + visible) / 2.0)
+    }
+}
@@ -1,2 +1,45 @@
+pub mod audio_output;
+pub mod audio_sink;
+pub mod color;
+pub mod equalizer;
+pub mod error;
+pub mod event;
+pub mod instance;
+pub mod layout;
+pub mod media;
+pub mod media_info;
+pub mod media_list;
+pub mod media_player;
+pub mod playlist;
+pub mod playlist_file;
+pub mod sidecar;
+pub mod source;
+pub mod subtitle;
+pub mod swvideo;
+pub mod test_pattern;
+mod tools;
+pub mod track;
+pub mod triple_buffer;
 pub mod vlc;
 pub mod vlcvideo;
+
+pub use audio_output::{AudioDevice, AudioOutput};
+pub use audio_sink::{
+    AudioFormat, AudioLevels, AudioSink, ChannelLevel, LevelMeter, NullSink, WavWriter,
+};
+pub use color::{ColorInfo, ColorSpace, Primaries, ToneMapping, Transfer};
+pub use equalizer::Equalizer;
+pub use error::{NeovideoError, Result};
+pub use event::{Event, EventManager, Subscription};
+pub use instance::Instance;
+pub use layout::{AspectRatio, ScaleMode, VideoQuad};
+pub use media::Media;
+pub use media_info::MediaInfo;
+pub use media_list::MediaList;
+pub use media_player::MediaPlayer;
+pub use playlist::{Playlist, Repeat};
+pub use playlist_file::{PlaylistEntry, PlaylistFormat};
+pub use source::{FrameNotifier, VideoSource};
+pub use subtitle::{Cue, Subtitles};
+pub use test_pattern::TestPatternSource;
+pub use track::{Track, TrackDescription, TrackDetails};
@@ -0,0 +1,244 @@
+use std::path::Path;
+use std::slice;
+
+use super::error::{NeovideoError, Result};
+use super::event::EventManager;
+use super::instance::Instance;
+use super::media_info::MediaInfo;
+use super::tools::{from_cstr_owned, from_cstr_ref, path_to_cstr, path_to_uri_cstr, to_cstr};
+use super::track::Track;
+use super::vlc::{
+    libvlc_media_add_option, libvlc_media_add_option_flag, libvlc_media_duplicate,
+    libvlc_media_event_manager, libvlc_media_get_duration, libvlc_media_get_meta,
+    libvlc_media_get_mrl, libvlc_media_get_state, libvlc_media_is_parsed,
+    libvlc_media_new_location, libvlc_media_new_path, libvlc_media_option_unique,
+    libvlc_media_parse_async, libvlc_media_release, libvlc_media_retain, libvlc_media_save_meta,
+    libvlc_media_set_meta, libvlc_media_slave_t, libvlc_media_slave_type_t,
+    libvlc_media_slaves_add, libvlc_media_slaves_get, libvlc_media_slaves_release, libvlc_media_t,
+    Meta, State,
+};
+
+/// A libvlc media item. Cloning retains the underlying handle, dropping releases it.
+pub struct Media {
+    ptr: *mut libvlc_media_t,
+}
+
+unsafe impl Send for Media {}
+unsafe impl Sync for Media {}
+
+impl Media {
+    /// Creates a media from an MRL such as `file:///...` or `https://...`.
+    pub fn new_location(instance: &Instance, mrl: &str) -> Result<Media> {
+        let c_mrl = to_cstr(mrl).ok_or_else(|| NeovideoError::InvalidMrl(mrl.to_owned()))?;
+        unsafe {
+            Media::from_ptr(
+                libvlc_media_new_location(instance.raw(), c_mrl.as_ptr()),
+                mrl,
+            )
+        }
+    }
+
+    /// Creates a media from a local filesystem path.
+    pub fn new_path<P: AsRef<Path>>(instance: &Instance, path: P) -> Result<Media> {
+        let path = path.as_ref();
+        let c_path = path_to_cstr(path)
+            .ok_or_else(|| NeovideoError::InvalidMrl(path.to_string_lossy().into_owned()))?;
+        unsafe {
+            Media::from_ptr(
+                libvlc_media_new_path(instance.raw(), c_path.as_ptr()),
+                &path.to_string_lossy(),
+            )
+        }
+    }
+
+    /// Creates a media from an MRL if `url` contains `://`, from a local path otherwise.
+    pub fn new_url<P: AsRef<Path>>(instance: &Instance, url: P) -> Result<Media> {
+        let url = url.as_ref();
+        let mrl = url
+            .to_str()
+            .ok_or_else(|| NeovideoError::InvalidMrl(url.to_string_lossy().into_owned()))?;
+        if mrl.contains("://") {
+            Media::new_location(instance, mrl)
+        } else {
+            Media::new_path(instance, url)
+        }
+    }
+
+    unsafe fn from_ptr(ptr: *mut libvlc_media_t, mrl: &str) -> Result<Media> {
+        if ptr.is_null() {
+            Err(NeovideoError::MediaCreation {
+                mrl: mrl.to_owned(),
+                reason: NeovideoError::last_vlc_error(),
+            })
+        } else {
+            Ok(Media { ptr })
+        }
+    }
+
+    /// Takes ownership of one reference to `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must be a valid, non-null media whose reference is not released elsewhere.
+    pub unsafe fn from_raw(ptr: *mut libvlc_media_t) -> Media {
+        Media { ptr }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> *mut libvlc_media_t {
+        self.ptr
+    }
+
+    /// A new media with the MRL, options, subtitle files and metadata of this one,
+    /// options added to either do not affect the other.
+    pub fn duplicate(&self) -> Result<Media> {
+        let mrl = self.mrl().unwrap_or_default();
+        unsafe { Media::from_ptr(libvlc_media_duplicate(self.ptr), &mrl) }
+    }
+
+    /// Adds an input option such as `:start-time=10`.
+    pub fn add_option(&self, option: &str) {
+        if let Some(option) = to_cstr(option) {
+            unsafe {
+                libvlc_media_add_option(self.ptr, option.as_ptr());
+            }
+        }
+    }
+
+    /// Adds an input option that replaces an earlier one of the same name.
+    pub fn set_option(&self, option: &str) {
+        if let Some(option) = to_cstr(option) {
+            unsafe {
+                libvlc_media_add_option_flag(self.ptr, option.as_ptr(), libvlc_media_option_unique);
+            }
+        }
+    }
+
+    /// Loads a subtitle file along with the media, call before playing it. A file that
+    /// is already attached is not added again.
+    pub fn add_subtitle_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let uri = path_to_uri_cstr(path)
+            .ok_or_else(|| NeovideoError::InvalidMrl(path.to_string_lossy().into_owned()))?;
+        if self.slaves().iter().any(|s| s.as_bytes() == uri.as_bytes()) {
+            return Ok(());
+        }
+        let ret = unsafe {
+            libvlc_media_slaves_add(
+                self.ptr,
+                libvlc_media_slave_type_t::libvlc_media_slave_type_subtitle,
+                // the highest priority, as for files the user picked
+                4,
+                uri.as_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(NeovideoError::vlc("could not add the subtitle file"));
+        }
+        Ok(())
+    }
+
+    /// URIs of the subtitle and audio files attached to the media.
+    pub fn slaves(&self) -> Vec<String> {
+        unsafe {
+            let mut slaves: *mut *mut libvlc_media_slave_t = std::ptr::null_mut();
+            let count = libvlc_media_slaves_get(self.ptr, &mut slaves);
+            if slaves.is_null() {
+                return Vec::new();
+            }
+            let uris = slice::from_raw_parts(slaves, count as usize)
+                .iter()
+                .filter(|slave| !slave.is_null())
+                .filter_map(|&slave| from_cstr_ref((*slave).psz_uri))
+                .collect();
+            libvlc_media_slaves_release(slaves, count);
+            uris
+        }
+    }
+
+    pub fn mrl(&self) -> Option<String> {
+        unsafe { from_cstr_owned(libvlc_media_get_mrl(self.ptr)) }
+    }
+
+    /// Duration in milliseconds, `None` while unknown.
+    pub fn duration(&self) -> Option<i64> {
+        let duration = unsafe { libvlc_media_get_duration(self.ptr) };
+        if duration < 0 {
+            None
+        } else {
+            Some(duration)
+        }
+    }
+
+    /// Starts reading the metadata of a local media in the background,
+    /// `MediaParsedChanged` is sent when done.
+    pub fn parse_async(&self) {
+        unsafe {
+            libvlc_media_parse_async(self.ptr);
+        }
+    }
+
+    pub fn is_parsed(&self) -> bool {
+        unsafe { libvlc_media_is_parsed(self.ptr) != 0 }
+    }
+
+    /// A metadata value, `None` if unset or not parsed yet.
+    pub fn meta(&self, meta: Meta) -> Option<String> {
+        unsafe { from_cstr_owned(libvlc_media_get_meta(self.ptr, meta)) }
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Changes a metadata value in memory, see `save_meta`.
+    pub fn set_meta(&self, meta: Meta, value: &str) {
+        if let Some(value) = to_cstr(value) {
+            unsafe {
+                libvlc_media_set_meta(self.ptr, meta, value.as_ptr());
+            }
+        }
+    }
+
+    /// Writes the changed metadata back to the file, if its format supports it.
+    pub fn save_meta(&self) -> Result<()> {
+        if unsafe { libvlc_media_save_meta(self.ptr) } == 0 {
+            return Err(NeovideoError::vlc("could not save the metadata"));
+        }
+        Ok(())
+    }
+
+    /// A snapshot of the metadata.
+    pub fn info(&self) -> MediaInfo {
+        MediaInfo::read(self)
+    }
+
+    /// The tracks found so far, empty until the media is parsed or playing.
+    pub fn tracks(&self) -> Vec<Track> {
+        unsafe { Track::list(self.ptr) }
+    }
+
+    pub fn state(&self) -> State {
+        unsafe { libvlc_media_get_state(self.ptr) }
+    }
+
+    pub fn event_manager(&self) -> EventManager {
+        EventManager::new(
+            unsafe { libvlc_media_event_manager(self.ptr) },
+            self.clone(),
+        )
+    }
+}
+
+impl Clone for Media {
+    fn clone(&self) -> Media {
+        unsafe {
+            libvlc_media_retain(self.ptr);
+        }
+        Media { ptr: self.ptr }
+    }
+}
+
+impl Drop for Media {
+    fn drop(&mut self) {
+        unsafe {
+            libvlc_media_release(self.ptr);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::media::Media;
+use super::vlc::Meta;
+
+/// A snapshot of the metadata of a media.
+///
+/// libvlc fills in most values once the media is parsed, see `Media::parse_async`, or
+/// once it starts playing. Before that only the title, which falls back to the file
+/// name, is usually known.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub mrl: Option<String>,
+    /// Duration in milliseconds.
+    pub duration: Option<i64>,
+    pub parsed: bool,
+    /// The values set, in `Meta::ALL` order.
+    values: Vec<(Meta, String)>,
+}
+
+impl MediaInfo {
+    pub(crate) fn read(media: &Media) -> MediaInfo {
+        MediaInfo {
+            mrl: media.mrl(),
+            duration: media.duration(),
+            parsed: media.is_parsed(),
+            values: Meta::ALL
+                .into_iter()
+                .filter_map(|meta| Some((meta, media.meta(meta)?)))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, meta: Meta) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(m, _)| *m == meta)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The values set, in `Meta::ALL` order.
+    pub fn iter(&self) -> impl Iterator<Item = (Meta, &str)> {
+        self.values
+            .iter()
+            .map(|(meta, value)| (*meta, value.as_str()))
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.get(Meta::Title)
+    }
+
+    pub fn artist(&self) -> Option<&str> {
+        self.get(Meta::Artist)
+    }
+
+    pub fn album(&self) -> Option<&str> {
+        self.get(Meta::Album)
+    }
+
+    pub fn show_name(&self) -> Option<&str> {
+        self.get(Meta::ShowName)
+    }
+
+    pub fn season(&self) -> Option<u32> {
+        self.get(Meta::Season)?.trim().parse().ok()
+    }
+
+    pub fn episode(&self) -> Option<u32> {
+        self.get(Meta::Episode)?.trim().parse().ok()
+    }
+
+    /// A one line description such as `Artist - Title` or `Show S01E02 - Title`.
+    pub fn display_title(&self) -> Option<String> {
+        let title = self.title();
+        let prefix = match (self.show_name(), self.season(), self.episode()) {
+            (Some(show), Some(season), Some(episode)) => {
+                Some(format!("{} S{:02}E{:02}", show, season, episode))
+            }
+            (Some(show), _, _) => Some(show.to_owned()),
+            _ => self.artist().map(str::to_owned),
+        };
+        match (prefix, title) {
+            (Some(prefix), Some(title)) if prefix != title => {
+                Some(format!("{} - {}", prefix, title))
+            }
+            (Some(prefix), _) => Some(prefix),
+            (None, title) => title.map(str::to_owned),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use libc::c_int;
+
+use super::error::{NeovideoError, Result};
+use super::event::EventManager;
+use super::instance::Instance;
+use super::media::Media;
+use super::vlc::{
+    libvlc_media_list_add_media, libvlc_media_list_count, libvlc_media_list_event_manager,
+    libvlc_media_list_index_of_item, libvlc_media_list_insert_media, libvlc_media_list_is_readonly,
+    libvlc_media_list_item_at_index, libvlc_media_list_lock, libvlc_media_list_new,
+    libvlc_media_list_release, libvlc_media_list_remove_index, libvlc_media_list_retain,
+    libvlc_media_list_t, libvlc_media_list_unlock,
+};
+
+/// A libvlc media list. Cloning retains the underlying handle, dropping releases it.
+///
+/// Every method takes the list lock for its own duration, so callers never lock by hand.
+pub struct MediaList {
+    ptr: *mut libvlc_media_list_t,
+}
+
+unsafe impl Send for MediaList {}
+unsafe impl Sync for MediaList {}
+
+impl MediaList {
+    pub fn new(instance: &Instance) -> Result<MediaList> {
+        let ptr = unsafe { libvlc_media_list_new(instance.raw()) };
+        if ptr.is_null() {
+            Err(NeovideoError::vlc("libvlc_media_list_new failed"))
+        } else {
+            Ok(MediaList { ptr })
+        }
+    }
+
+    /// Takes ownership of one reference to `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must be a valid, non-null list whose reference is not released elsewhere.
+    pub unsafe fn from_raw(ptr: *mut libvlc_media_list_t) -> MediaList {
+        MediaList { ptr }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> *mut libvlc_media_list_t {
+        self.ptr
+    }
+
+    fn locked<R>(&self, f: impl FnOnce(*mut libvlc_media_list_t) -> R) -> R {
+        unsafe {
+            libvlc_media_list_lock(self.ptr);
+            let r = f(self.ptr);
+            libvlc_media_list_unlock(self.ptr);
+            r
+        }
+    }
+
+    /// Returns `false` if the list is read-only.
+    pub fn add_media(&self, media: &Media) -> bool {
+        self.locked(|ptr| unsafe { libvlc_media_list_add_media(ptr, media.raw()) == 0 })
+    }
+
+    /// Returns `false` if the list is read-only or `index` is out of range.
+    pub fn insert_media(&self, media: &Media, index: usize) -> bool {
+        self.locked(|ptr| unsafe {
+            libvlc_media_list_insert_media(ptr, media.raw(), index as c_int) == 0
+        })
+    }
+
+    /// Returns `false` if the list is read-only or `index` is out of range.
+    pub fn remove_index(&self, index: usize) -> bool {
+        self.locked(|ptr| unsafe { libvlc_media_list_remove_index(ptr, index as c_int) == 0 })
+    }
+
+    pub fn count(&self) -> usize {
+        self.locked(|ptr| unsafe { libvlc_media_list_count(ptr) }.max(0) as usize)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    pub fn item_at_index(&self, index: usize) -> Option<Media> {
+        let ptr =
+            self.locked(|ptr| unsafe { libvlc_media_list_item_at_index(ptr, index as c_int) });
+        if ptr.is_null() {
+            None
+        } else {
+            // the returned item is already retained for us
+            Some(unsafe { Media::from_raw(ptr) })
+        }
+    }
+
+    pub fn index_of_item(&self, media: &Media) -> Option<usize> {
+        let index = self.locked(|ptr| unsafe { libvlc_media_list_index_of_item(ptr, media.raw()) });
+        if index < 0 {
+            None
+        } else {
+            Some(index as usize)
+        }
+    }
+
+    pub fn is_readonly(&self) -> bool {
+        unsafe { libvlc_media_list_is_readonly(self.ptr) != 0 }
+    }
+
+    pub fn event_manager(&self) -> EventManager {
+        EventManager::new(
+            unsafe { libvlc_media_list_event_manager(self.ptr) },
+            self.clone(),
+        )
+    }
+}
+
+impl Clone for MediaList {
+    fn clone(&self) -> MediaList {
+        unsafe {
+            libvlc_media_list_retain(self.ptr);
+        }
+        MediaList { ptr: self.ptr }
+    }
+}
+
+impl Drop for MediaList {
+    fn drop(&mut self) {
+        unsafe {
+            libvlc_media_list_release(self.ptr);
+        }
+    }
+}
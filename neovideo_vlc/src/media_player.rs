@@ -0,0 +1,428 @@
+use std::path::Path;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use libc::c_int;
+
+use super::audio_output::AudioDevice;
+use super::equalizer::Equalizer;
+use super::error::{NeovideoError, Result};
+use super::event::EventManager;
+use super::instance::Instance;
+use super::media::Media;
+use super::tools::{from_cstr_owned, lock, path_to_uri_cstr, to_cstr};
+use super::track::TrackDescription;
+use super::vlc::{
+    libvlc_audio_get_channel, libvlc_audio_get_delay, libvlc_audio_get_mute,
+    libvlc_audio_get_track, libvlc_audio_get_track_description, libvlc_audio_get_volume,
+    libvlc_audio_output_device_enum, libvlc_audio_output_device_get,
+    libvlc_audio_output_device_set, libvlc_audio_output_set, libvlc_audio_set_channel,
+    libvlc_audio_set_delay, libvlc_audio_set_mute, libvlc_audio_set_track, libvlc_audio_set_volume,
+    libvlc_audio_toggle_mute, libvlc_media_player_add_slave, libvlc_media_player_can_pause,
+    libvlc_media_player_event_manager, libvlc_media_player_get_length,
+    libvlc_media_player_get_position, libvlc_media_player_get_rate, libvlc_media_player_get_state,
+    libvlc_media_player_get_time, libvlc_media_player_is_playing, libvlc_media_player_is_seekable,
+    libvlc_media_player_new, libvlc_media_player_new_from_media, libvlc_media_player_next_frame,
+    libvlc_media_player_pause, libvlc_media_player_play, libvlc_media_player_release,
+    libvlc_media_player_retain, libvlc_media_player_set_equalizer, libvlc_media_player_set_media,
+    libvlc_media_player_set_pause, libvlc_media_player_set_position, libvlc_media_player_set_rate,
+    libvlc_media_player_set_time, libvlc_media_player_stop, libvlc_media_player_t,
+    libvlc_media_slave_type_t, libvlc_video_get_spu, libvlc_video_get_spu_delay,
+    libvlc_video_get_spu_description, libvlc_video_get_track, libvlc_video_get_track_description,
+    libvlc_video_set_spu, libvlc_video_set_spu_delay, libvlc_video_set_track, AudioChannel, State,
+    TrackType,
+};
+
+/// Highest volume libvlc accepts, in percent.
+pub const MAX_VOLUME: i32 = 200;
+
+/// A libvlc media player. Cloning retains the underlying handle, dropping releases it.
+pub struct MediaPlayer {
+    ptr: *mut libvlc_media_player_t,
+    /// What the video and audio callbacks point to, shared by the clones and dropped
+    /// after the last of them released the player.
+    callback_data: Arc<Mutex<Vec<Arc<dyn Send + Sync>>>>,
+}
+
+unsafe impl Send for MediaPlayer {}
+unsafe impl Sync for MediaPlayer {}
+
+impl MediaPlayer {
+    pub fn new(instance: &Instance) -> Result<MediaPlayer> {
+        unsafe { MediaPlayer::from_ptr(libvlc_media_player_new(instance.raw())) }
+    }
+
+    pub fn with_media(media: &Media) -> Result<MediaPlayer> {
+        unsafe { MediaPlayer::from_ptr(libvlc_media_player_new_from_media(media.raw())) }
+    }
+
+    unsafe fn from_ptr(ptr: *mut libvlc_media_player_t) -> Result<MediaPlayer> {
+        if ptr.is_null() {
+            Err(NeovideoError::PlayerCreation(
+                NeovideoError::last_vlc_error(),
+            ))
+        } else {
+            Ok(MediaPlayer::from_raw(ptr))
+        }
+    }
+
+    /// Takes ownership of one reference to `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must be a valid, non-null player whose reference is not released elsewhere.
+    pub unsafe fn from_raw(ptr: *mut libvlc_media_player_t) -> MediaPlayer {
+        MediaPlayer {
+            ptr,
+            callback_data: Arc::default(),
+        }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> *mut libvlc_media_player_t {
+        self.ptr
+    }
+
+    /// Keeps `data` alive for as long as any clone of the player, for callbacks that
+    /// were handed a pointer into it.
+    pub(crate) fn keep_alive(&self, data: Arc<dyn Send + Sync>) {
+        lock(&self.callback_data).push(data);
+    }
+
+    /// Sets the media to play next, the player keeps its own reference.
+    pub fn set_media(&self, media: Option<&Media>) {
+        unsafe {
+            libvlc_media_player_set_media(self.ptr, media.map_or(ptr::null_mut(), |m| m.raw()));
+        }
+    }
+
+    pub fn play(&self) -> Result<()> {
+        if unsafe { libvlc_media_player_play(self.ptr) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("playback could not be started"))
+        }
+    }
+
+    pub fn stop(&self) {
+        unsafe {
+            libvlc_media_player_stop(self.ptr);
+        }
+    }
+
+    /// Toggles between playing and paused.
+    pub fn pause(&self) {
+        unsafe {
+            libvlc_media_player_pause(self.ptr);
+        }
+    }
+
+    pub fn set_pause(&self, pause: bool) {
+        unsafe {
+            libvlc_media_player_set_pause(self.ptr, pause as c_int);
+        }
+    }
+
+    /// Displays the next frame and pauses, if the media supports it.
+    pub fn next_frame(&self) {
+        unsafe {
+            libvlc_media_player_next_frame(self.ptr);
+        }
+    }
+
+    /// Current time in milliseconds, `None` without media.
+    pub fn time(&self) -> Option<i64> {
+        let time = unsafe { libvlc_media_player_get_time(self.ptr) };
+        if time < 0 {
+            None
+        } else {
+            Some(time)
+        }
+    }
+
+    /// Seeks to `time` milliseconds, only effective on seekable media.
+    pub fn set_time(&self, time: i64) {
+        unsafe {
+            libvlc_media_player_set_time(self.ptr, time);
+        }
+    }
+
+    /// Current position in the range `0.0..=1.0`, `None` without media.
+    pub fn position(&self) -> Option<f32> {
+        let position = unsafe { libvlc_media_player_get_position(self.ptr) };
+        if position < 0.0 {
+            None
+        } else {
+            Some(position)
+        }
+    }
+
+    /// Seeks to `position` in the range `0.0..=1.0`, only effective on seekable media.
+    pub fn set_position(&self, position: f32) {
+        unsafe {
+            libvlc_media_player_set_position(self.ptr, position);
+        }
+    }
+
+    /// Media length in milliseconds, `None` while unknown.
+    pub fn length(&self) -> Option<i64> {
+        let length = unsafe { libvlc_media_player_get_length(self.ptr) };
+        if length <= 0 {
+            None
+        } else {
+            Some(length)
+        }
+    }
+
+    pub fn rate(&self) -> f32 {
+        unsafe { libvlc_media_player_get_rate(self.ptr) }
+    }
+
+    /// Sets the playback rate, 1.0 being normal speed.
+    pub fn set_rate(&self, rate: f32) -> Result<()> {
+        if unsafe { libvlc_media_player_set_rate(self.ptr, rate) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("playback rate not supported"))
+        }
+    }
+
+    pub fn is_seekable(&self) -> bool {
+        unsafe { libvlc_media_player_is_seekable(self.ptr) != 0 }
+    }
+
+    pub fn can_pause(&self) -> bool {
+        unsafe { libvlc_media_player_can_pause(self.ptr) != 0 }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        unsafe { libvlc_media_player_is_playing(self.ptr) != 0 }
+    }
+
+    pub fn state(&self) -> State {
+        unsafe { libvlc_media_player_get_state(self.ptr) }
+    }
+
+    pub fn event_manager(&self) -> EventManager {
+        EventManager::new(
+            unsafe { libvlc_media_player_event_manager(self.ptr) },
+            self.clone(),
+        )
+    }
+
+    /// Volume in percent, `None` while there is no audio output. Values above 100 amplify
+    /// the sound up to `MAX_VOLUME`.
+    pub fn volume(&self) -> Option<i32> {
+        let volume = unsafe { libvlc_audio_get_volume(self.ptr) };
+        if volume < 0 {
+            None
+        } else {
+            Some(volume)
+        }
+    }
+
+    pub fn set_volume(&self, volume: i32) -> Result<()> {
+        if !(0..=MAX_VOLUME).contains(&volume) {
+            return Err(NeovideoError::InvalidArgument(format!(
+                "volume {} is not within 0 to {}",
+                volume, MAX_VOLUME
+            )));
+        }
+        if unsafe { libvlc_audio_set_volume(self.ptr, volume) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not change the volume"))
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        unsafe { libvlc_audio_get_mute(self.ptr) > 0 }
+    }
+
+    pub fn set_mute(&self, mute: bool) {
+        unsafe {
+            libvlc_audio_set_mute(self.ptr, mute as c_int);
+        }
+    }
+
+    pub fn toggle_mute(&self) {
+        unsafe {
+            libvlc_audio_toggle_mute(self.ptr);
+        }
+    }
+
+    /// Audio delay in milliseconds, positive values play the sound later.
+    pub fn audio_delay(&self) -> i64 {
+        unsafe { libvlc_audio_get_delay(self.ptr) / 1000 }
+    }
+
+    pub fn set_audio_delay(&self, delay: i64) -> Result<()> {
+        if unsafe { libvlc_audio_set_delay(self.ptr, delay * 1000) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not change the audio delay"))
+        }
+    }
+
+    /// The stereo mode, `None` while there is no audio output.
+    pub fn channel(&self) -> Option<AudioChannel> {
+        AudioChannel::from_raw(unsafe { libvlc_audio_get_channel(self.ptr) })
+    }
+
+    pub fn set_channel(&self, channel: AudioChannel) -> Result<()> {
+        if unsafe { libvlc_audio_set_channel(self.ptr, channel as c_int) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc(
+                "could not change the audio channel mode",
+            ))
+        }
+    }
+
+    /// Switches to the audio output module `name`, takes effect for the next media.
+    pub fn set_audio_output(&self, name: &str) -> Result<()> {
+        let name = to_cstr(name).ok_or_else(|| invalid_name("audio output", name))?;
+        if unsafe { libvlc_audio_output_set(self.ptr, name.as_ptr()) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not change the audio output"))
+        }
+    }
+
+    /// The devices of the current audio output module.
+    pub fn audio_devices(&self) -> Vec<AudioDevice> {
+        unsafe { AudioDevice::from_list(libvlc_audio_output_device_enum(self.ptr)) }
+    }
+
+    /// Id of the device in use, `None` for the system default.
+    pub fn audio_device(&self) -> Option<String> {
+        unsafe { from_cstr_owned(libvlc_audio_output_device_get(self.ptr)) }
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Switches the current audio output module to device `id`.
+    pub fn set_audio_device(&self, id: &str) -> Result<()> {
+        let id = to_cstr(id).ok_or_else(|| invalid_name("audio device", id))?;
+        unsafe {
+            libvlc_audio_output_device_set(self.ptr, ptr::null(), id.as_ptr());
+        }
+        Ok(())
+    }
+
+    /// The tracks of `track_type` that can be selected, audio and subtitles include a
+    /// `-1` entry that disables them.
+    pub fn track_descriptions(&self, track_type: TrackType) -> Vec<TrackDescription> {
+        unsafe {
+            let list = match track_type {
+                TrackType::Audio => libvlc_audio_get_track_description(self.ptr),
+                TrackType::Video => libvlc_video_get_track_description(self.ptr),
+                TrackType::Text => libvlc_video_get_spu_description(self.ptr),
+                TrackType::Unknown => return Vec::new(),
+            };
+            TrackDescription::from_list(list)
+        }
+    }
+
+    /// The id of the selected track of `track_type`, `None` if disabled or unknown.
+    pub fn track(&self, track_type: TrackType) -> Option<i32> {
+        let id = unsafe {
+            match track_type {
+                TrackType::Audio => libvlc_audio_get_track(self.ptr),
+                TrackType::Video => libvlc_video_get_track(self.ptr),
+                TrackType::Text => libvlc_video_get_spu(self.ptr),
+                TrackType::Unknown => -1,
+            }
+        };
+        if id < 0 {
+            None
+        } else {
+            Some(id)
+        }
+    }
+
+    /// Selects the track with the given id, `None` disables the type.
+    pub fn set_track(&self, track_type: TrackType, id: Option<i32>) -> Result<()> {
+        let id = id.unwrap_or(-1) as c_int;
+        let ret = unsafe {
+            match track_type {
+                TrackType::Audio => libvlc_audio_set_track(self.ptr, id),
+                TrackType::Video => libvlc_video_set_track(self.ptr, id),
+                TrackType::Text => libvlc_video_set_spu(self.ptr, id),
+                TrackType::Unknown => -1,
+            }
+        };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("no such track"))
+        }
+    }
+
+    /// Loads a subtitle file into the playing media, `select` switches to it.
+    pub fn add_subtitle_file<P: AsRef<Path>>(&self, path: P, select: bool) -> Result<()> {
+        let path = path.as_ref();
+        let uri = path_to_uri_cstr(path)
+            .ok_or_else(|| NeovideoError::InvalidMrl(path.to_string_lossy().into_owned()))?;
+        let ret = unsafe {
+            libvlc_media_player_add_slave(
+                self.ptr,
+                libvlc_media_slave_type_t::libvlc_media_slave_type_subtitle,
+                uri.as_ptr(),
+                select,
+            )
+        };
+        if ret != 0 {
+            return Err(NeovideoError::vlc("could not load the subtitle file"));
+        }
+        Ok(())
+    }
+
+    /// Subtitle delay in milliseconds, positive values show subtitles later.
+    pub fn subtitle_delay(&self) -> i64 {
+        unsafe { libvlc_video_get_spu_delay(self.ptr) / 1000 }
+    }
+
+    pub fn set_subtitle_delay(&self, delay: i64) -> Result<()> {
+        if unsafe { libvlc_video_set_spu_delay(self.ptr, delay * 1000) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not change the subtitle delay"))
+        }
+    }
+
+    /// Applies the equalizer settings, `None` disables equalization.
+    ///
+    /// The player copies the settings, so the equalizer can be dropped afterwards.
+    pub fn set_equalizer(&self, equalizer: Option<&Equalizer>) -> Result<()> {
+        let equalizer = equalizer.map_or(ptr::null_mut(), |e| e.raw());
+        if unsafe { libvlc_media_player_set_equalizer(self.ptr, equalizer) } == 0 {
+            Ok(())
+        } else {
+            Err(NeovideoError::vlc("could not apply the equalizer"))
+        }
+    }
+}
+
+impl Clone for MediaPlayer {
+    fn clone(&self) -> MediaPlayer {
+        unsafe {
+            libvlc_media_player_retain(self.ptr);
+        }
+        MediaPlayer {
+            ptr: self.ptr,
+            callback_data: self.callback_data.clone(),
+        }
+    }
+}
+
+impl Drop for MediaPlayer {
+    fn drop(&mut self) {
+        unsafe {
+            libvlc_media_player_release(self.ptr);
+        }
+    }
+}
+
+/// A name libvlc cannot be handed, it contains a NUL.
+fn invalid_name(what: &str, name: &str) -> NeovideoError {
+    NeovideoError::InvalidArgument(format!("invalid {} '{}'", what, name.escape_debug()))
+}
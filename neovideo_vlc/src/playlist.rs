@@ -0,0 +1,343 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::{NeovideoError, Result};
+use super::instance::Instance;
+use super::media::Media;
+use super::media_list::MediaList;
+use super::playlist_file::{self, PlaylistEntry};
+use super::vlc::Meta;
+
+/// What happens when an item ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Repeat {
+    /// Go on with the next item, stop after the last one.
+    #[default]
+    Off,
+    /// Play the same item again.
+    One,
+    /// Go on with the next item, start over after the last one.
+    All,
+}
+
+impl Repeat {
+    pub const ALL: [Repeat; 3] = [Repeat::Off, Repeat::One, Repeat::All];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Repeat::Off => "Off",
+            Repeat::One => "One",
+            Repeat::All => "All",
+        }
+    }
+}
+
+/// An ordered list of media with a current item, backed by a libvlc media list.
+///
+/// Indices are positions in the list. The play order is the list order, or a random
+/// permutation of it while shuffling.
+///
+/// Playback is left to the caller rather than a `libvlc_media_list_player`: that player
+/// has no shuffle, and it starts every item itself, while `VLCVideo` has to set up the
+/// video output, audio sink and input options of each media before playing it.
+pub struct Playlist {
+    instance: Instance,
+    list: MediaList,
+    current: Option<usize>,
+    repeat: Repeat,
+    shuffle: bool,
+    /// Play order as list indices.
+    order: Vec<usize>,
+    /// Set when the current item was removed, the order position to continue from.
+    resume_at: Option<usize>,
+    rng: u64,
+}
+
+impl Playlist {
+    pub fn new(instance: &Instance) -> Result<Playlist> {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Ok(Playlist {
+            instance: instance.clone(),
+            list: MediaList::new(instance)?,
+            current: None,
+            repeat: Repeat::Off,
+            shuffle: false,
+            order: Vec::new(),
+            resume_at: None,
+            // xorshift must not start from zero
+            rng: seed | 1,
+        })
+    }
+
+    pub fn media_list(&self) -> &MediaList {
+        &self.list
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Media> {
+        self.list.item_at_index(index)
+    }
+
+    /// Appends a local path, or an MRL if it contains `://`.
+    pub fn add_url<P: AsRef<Path>>(&mut self, url: P) -> Result<usize> {
+        let media = Media::new_url(&self.instance, url)?;
+        let index = self.len();
+        if !self.insert(index, &media) {
+            return Err(NeovideoError::vlc(
+                "could not add the media to the playlist",
+            ));
+        }
+        Ok(index)
+    }
+
+    /// Appends the items of an M3U, PLS or XSPF file, returns how many were added.
+    /// Nothing is added if any item cannot be opened.
+    pub fn import<P: AsRef<Path>>(&mut self, path: P) -> Result<usize> {
+        let media = playlist_file::load(path)?
+            .iter()
+            .map(|entry| {
+                let media = Media::new_url(&self.instance, &entry.location)?;
+                if let Some(title) = &entry.title {
+                    media.set_meta(Meta::Title, title);
+                }
+                Ok(media)
+            })
+            .collect::<Result<Vec<Media>>>()?;
+        Ok(media.iter().filter(|media| self.add(media)).count())
+    }
+
+    /// Writes the list in the format given by the file extension, M3U if unknown.
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let entries: Vec<PlaylistEntry> = (0..self.len())
+            .filter_map(|index| self.get(index))
+            .filter_map(|media| {
+                let mrl = media.mrl()?;
+                Some(PlaylistEntry {
+                    location: playlist_file::mrl_to_path(&mrl).unwrap_or(mrl),
+                    title: media.meta(Meta::Title),
+                    duration: media.duration(),
+                })
+            })
+            .collect();
+        playlist_file::save(path, &entries)
+    }
+
+    /// Returns `false` if the list is read-only.
+    pub fn add(&mut self, media: &Media) -> bool {
+        let index = self.len();
+        self.insert(index, media)
+    }
+
+    /// Inserts `media` before `index` and starts parsing its metadata, returns `false`
+    /// if the list is read-only or `index` is out of range.
+    pub fn insert(&mut self, index: usize, media: &Media) -> bool {
+        if index > self.len() || !self.list.insert_media(media, index) {
+            return false;
+        }
+        // titles for the list, local files only
+        if !media.is_parsed() {
+            media.parse_async();
+        }
+        self.remap(|i| if i >= index { i + 1 } else { i });
+        let at = if self.shuffle {
+            // somewhere after the current item, so that it gets played this round
+            let from = self.order_pos().map_or(0, |pos| pos + 1);
+            from + self.random(self.order.len() - from + 1)
+        } else {
+            index
+        };
+        self.order.insert(at, index);
+        // inserted at the position to continue from, the new item is played next
+        self.resume_at = self
+            .resume_at
+            .map(|pos| if at < pos { pos + 1 } else { pos });
+        true
+    }
+
+    /// Returns `false` if the list is read-only or `index` is out of range.
+    pub fn remove(&mut self, index: usize) -> bool {
+        if !self.list.remove_index(index) {
+            return false;
+        }
+        let pos = self.order.iter().position(|&i| i == index);
+        if let Some(pos) = pos {
+            self.order.remove(pos);
+            self.resume_at = self.resume_at.map(|at| if pos < at { at - 1 } else { at });
+        }
+        if self.current == Some(index) {
+            // the item after the removed one moves up into its place
+            self.current = None;
+            self.resume_at = pos;
+        }
+        self.remap(|i| if i > index { i - 1 } else { i });
+        true
+    }
+
+    /// Moves the item at `from` to `to`, shifting the items in between.
+    pub fn move_item(&mut self, from: usize, to: usize) -> bool {
+        let len = self.len();
+        if from >= len || to >= len {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+        let media = match self.list.item_at_index(from) {
+            Some(media) => media,
+            None => return false,
+        };
+        if !self.list.remove_index(from) {
+            return false;
+        }
+        if !self.list.insert_media(&media, to) {
+            // put it back where it was
+            self.list.insert_media(&media, from);
+            return false;
+        }
+        self.remap(|i| {
+            if i == from {
+                to
+            } else if from < to && i > from && i <= to {
+                i - 1
+            } else if to < from && i >= to && i < from {
+                i + 1
+            } else {
+                i
+            }
+        });
+        if !self.shuffle {
+            self.order = (0..len).collect();
+        }
+        true
+    }
+
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    pub fn current_media(&self) -> Option<Media> {
+        self.current.and_then(|index| self.get(index))
+    }
+
+    /// Makes `index` the current item, `None` if it is out of range.
+    pub fn set_current(&mut self, index: usize) -> Option<usize> {
+        if index >= self.len() {
+            return None;
+        }
+        self.current = Some(index);
+        self.resume_at = None;
+        self.current
+    }
+
+    pub fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    pub fn set_repeat(&mut self, repeat: Repeat) {
+        self.repeat = repeat;
+    }
+
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    /// Shuffling plays the items not played yet in a random order, starting over
+    /// reshuffles.
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        if shuffle == self.shuffle {
+            return;
+        }
+        self.shuffle = shuffle;
+        self.reorder();
+    }
+
+    /// Moves on to the next item in play order, returns the new current item. At the end
+    /// it starts over with `Repeat::All` and returns `None` otherwise.
+    pub fn next_item(&mut self) -> Option<usize> {
+        let pos = match (self.resume_at.take(), self.order_pos()) {
+            (Some(pos), _) => pos,
+            (None, Some(pos)) => pos + 1,
+            (None, None) => 0,
+        };
+        self.current = if pos < self.order.len() {
+            Some(self.order[pos])
+        } else if self.repeat == Repeat::All && !self.order.is_empty() {
+            self.current = None;
+            self.reorder();
+            Some(self.order[0])
+        } else {
+            None
+        };
+        self.current
+    }
+
+    /// Moves back to the previous item in play order, returns the new current item.
+    pub fn previous_item(&mut self) -> Option<usize> {
+        let pos = match (self.resume_at.take(), self.order_pos()) {
+            (Some(pos), _) | (None, Some(pos)) => pos,
+            (None, None) => 0,
+        };
+        self.current = match pos.checked_sub(1) {
+            Some(pos) => self.order.get(pos).copied(),
+            None if self.repeat == Repeat::All => self.order.last().copied(),
+            None => self.order.first().copied(),
+        };
+        self.current
+    }
+
+    /// Called when the current item ended, the same item with `Repeat::One` and `next_item`
+    /// otherwise.
+    pub fn advance(&mut self) -> Option<usize> {
+        match self.current {
+            Some(current) if self.repeat == Repeat::One => Some(current),
+            _ => self.next_item(),
+        }
+    }
+
+    fn order_pos(&self) -> Option<usize> {
+        let current = self.current?;
+        self.order.iter().position(|&i| i == current)
+    }
+
+    fn remap(&mut self, f: impl Fn(usize) -> usize) {
+        for i in &mut self.order {
+            *i = f(*i);
+        }
+        self.current = self.current.map(&f);
+    }
+
+    /// Rebuilds the play order, a shuffled one starts with the current item.
+    fn reorder(&mut self) {
+        let len = self.len();
+        self.order = (0..len).collect();
+        self.resume_at = None;
+        if !self.shuffle {
+            return;
+        }
+        // Fisher-Yates
+        for i in (1..len).rev() {
+            let j = self.random(i + 1);
+            self.order.swap(i, j);
+        }
+        if let Some(pos) = self.order_pos() {
+            self.order.swap(0, pos);
+        }
+    }
+
+    /// A number in `0..n`, xorshift is plenty for shuffling.
+    fn random(&mut self, n: usize) -> usize {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng % n as u64) as usize
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::error::{NeovideoError, Result};
+use super::tools::{path_to_file_uri, percent_decode, percent_encode};
+
+/// One item of a playlist file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    /// A local path or an MRL, relative paths are resolved when parsing.
+    pub location: String,
+    pub title: Option<String>,
+    /// Duration in milliseconds.
+    pub duration: Option<i64>,
+}
+
+impl PlaylistEntry {
+    pub fn new(location: &str) -> PlaylistEntry {
+        PlaylistEntry {
+            location: location.to_owned(),
+            ..PlaylistEntry::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    /// Extended M3U, also M3U8.
+    M3u,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    /// Guesses the format from the file extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<PlaylistFormat> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(PlaylistFormat::M3u),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the start of the file.
+    pub fn sniff(text: &str) -> PlaylistFormat {
+        let start = text.trim_start_matches('\u{feff}').trim_start();
+        if start.starts_with("<?xml") || start.starts_with("<playlist") {
+            PlaylistFormat::Xspf
+        } else if matches!(start.get(..10), Some(head) if head.eq_ignore_ascii_case("[playlist]")) {
+            PlaylistFormat::Pls
+        } else {
+            PlaylistFormat::M3u
+        }
+    }
+
+    /// Parses a playlist, relative locations are resolved against `base` if given.
+    pub fn parse(self, text: &str, base: Option<&Path>) -> Result<Vec<PlaylistEntry>> {
+        let text = text.trim_start_matches('\u{feff}');
+        let mut entries = match self {
+            PlaylistFormat::M3u => parse_m3u(text)?,
+            PlaylistFormat::Pls => parse_pls(text)?,
+            PlaylistFormat::Xspf => parse_xspf(text)?,
+        };
+        for entry in &mut entries {
+            entry.location = resolve(&entry.location, base);
+        }
+        Ok(entries)
+    }
+
+    pub fn write(self, entries: &[PlaylistEntry]) -> String {
+        match self {
+            PlaylistFormat::M3u => write_m3u(entries),
+            PlaylistFormat::Pls => write_pls(entries),
+            PlaylistFormat::Xspf => write_xspf(entries),
+        }
+    }
+}
+
+/// Reads a playlist file, relative locations are resolved against its directory.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<PlaylistEntry>> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| io_error(path, e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let format = PlaylistFormat::from_path(path).unwrap_or_else(|| PlaylistFormat::sniff(&text));
+    format.parse(&text, path.parent())
+}
+
+/// Writes a playlist file in the format given by its extension, M3U if unknown.
+pub fn save<P: AsRef<Path>>(path: P, entries: &[PlaylistEntry]) -> Result<()> {
+    let path = path.as_ref();
+    let format = PlaylistFormat::from_path(path).unwrap_or(PlaylistFormat::M3u);
+    std::fs::write(path, format.write(entries)).map_err(|e| io_error(path, e))
+}
+
+/// The local path of a `file://` MRL, as libvlc builds them for paths.
+pub fn mrl_to_path(mrl: &str) -> Option<String> {
+    let path = mrl.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+    let path = percent_decode(path);
+    // file:///C:/video.mkv
+    let drive = matches!(path.as_bytes(), [b'/', letter, b':', ..] if letter.is_ascii_alphabetic());
+    Some(if cfg!(windows) && drive {
+        path[1..].to_owned()
+    } else {
+        path
+    })
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> NeovideoError {
+    NeovideoError::Io {
+        path: path.to_string_lossy().into_owned(),
+        reason: e.to_string(),
+    }
+}
+
+fn parse_error(line: usize, reason: &str) -> NeovideoError {
+    NeovideoError::PlaylistParse {
+        line,
+        reason: reason.to_owned(),
+    }
+}
+
+fn is_mrl(location: &str) -> bool {
+    location.contains("://")
+}
+
+fn resolve(location: &str, base: Option<&Path>) -> String {
+    match base {
+        Some(base) if !is_mrl(location) && !Path::new(location).is_absolute() => {
+            base.join(location).to_string_lossy().into_owned()
+        }
+        _ => location.to_owned(),
+    }
+}
+
+/// Seconds as written in M3U and PLS files, negative meaning unknown.
+fn parse_seconds(value: &str) -> Option<Option<i64>> {
+    let seconds: f64 = value.trim().parse().ok()?;
+    Some(if seconds < 0.0 {
+        None
+    } else {
+        Some((seconds * 1000.0).round() as i64)
+    })
+}
+
+fn write_seconds(duration: Option<i64>) -> i64 {
+    duration.map_or(-1, |ms| (ms + 500) / 1000)
+}
+
+fn parse_m3u(text: &str) -> Result<Vec<PlaylistEntry>> {
+    let mut entries = Vec::new();
+    let mut info: Option<(Option<i64>, Option<String>)> = None;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:<seconds> [attributes],<title>, commas may appear in quoted attributes
+            let mut quoted = false;
+            let comma = extinf.find(|c| {
+                if c == '"' {
+                    quoted = !quoted;
+                }
+                c == ',' && !quoted
+            });
+            let (head, title) = match comma {
+                Some(comma) => (&extinf[..comma], extinf[comma + 1..].trim()),
+                None => (extinf, ""),
+            };
+            let seconds = head.split_whitespace().next().unwrap_or("");
+            let duration = parse_seconds(seconds)
+                .ok_or_else(|| parse_error(i + 1, "invalid #EXTINF duration"))?;
+            let title = if title.is_empty() {
+                None
+            } else {
+                Some(title.to_owned())
+            };
+            info = Some((duration, title));
+        } else if line.is_empty() || line.starts_with('#') {
+            continue;
+        } else {
+            let (duration, title) = info.take().unwrap_or_default();
+            entries.push(PlaylistEntry {
+                location: line.to_owned(),
+                title,
+                duration,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn write_m3u(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        if entry.title.is_some() || entry.duration.is_some() {
+            let _ = writeln!(
+                out,
+                "#EXTINF:{},{}",
+                write_seconds(entry.duration),
+                entry.title.as_deref().unwrap_or("")
+            );
+        }
+        out.push_str(&entry.location);
+        out.push('\n');
+    }
+    out
+}
+
+fn parse_pls(text: &str) -> Result<Vec<PlaylistEntry>> {
+    let mut entries: BTreeMap<usize, (usize, PlaylistEntry)> = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with(';')
+            || line.starts_with('#')
+            || line.starts_with('[')
+        {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| parse_error(i + 1, "expected key=value"))?;
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        let field = ["file", "title", "length"]
+            .into_iter()
+            .find(|field| key.starts_with(field));
+        let field = match field {
+            Some(field) => field,
+            // NumberOfEntries, Version and the like
+            None => continue,
+        };
+        let n: usize = key[field.len()..]
+            .parse()
+            .map_err(|_| parse_error(i + 1, "invalid entry number"))?;
+        let (_, entry) = entries
+            .entry(n)
+            .or_insert((i + 1, PlaylistEntry::default()));
+        match field {
+            "file" => entry.location = value.to_owned(),
+            "title" if !value.is_empty() => entry.title = Some(value.to_owned()),
+            "length" => {
+                entry.duration =
+                    parse_seconds(value).ok_or_else(|| parse_error(i + 1, "invalid length"))?
+            }
+            _ => {}
+        }
+    }
+    entries
+        .into_values()
+        .map(|(line, entry)| {
+            if entry.location.is_empty() {
+                Err(parse_error(line, "entry without a File"))
+            } else {
+                Ok(entry)
+            }
+        })
+        .collect()
+}
+
+fn write_pls(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (i, entry) in entries.iter().enumerate() {
+        let n = i + 1;
+        let _ = writeln!(out, "File{}={}", n, entry.location);
+        if let Some(title) = &entry.title {
+            let _ = writeln!(out, "Title{}={}", n, title);
+        }
+        let _ = writeln!(out, "Length{}={}", n, write_seconds(entry.duration));
+    }
+    let _ = writeln!(out, "NumberOfEntries={}", entries.len());
+    out.push_str("Version=2\n");
+    out
+}
+
+fn parse_xspf(text: &str) -> Result<Vec<PlaylistEntry>> {
+    let (list_offset, track_list) = match elements(text, "trackList").next() {
+        Some(track_list) => track_list,
+        None => return Ok(Vec::new()),
+    };
+    let mut entries = Vec::new();
+    for (offset, track) in elements(track_list, "track") {
+        let line = text[..list_offset + offset].matches('\n').count() + 1;
+        let field = |name| {
+            elements(track, name)
+                .next()
+                .map(|(_, value)| unescape(value.trim()))
+        };
+        let location =
+            field("location").ok_or_else(|| parse_error(line, "track without a location"))?;
+        let duration = match field("duration") {
+            Some(duration) => Some(
+                duration
+                    .parse()
+                    .map_err(|_| parse_error(line, "invalid duration"))?,
+            ),
+            None => None,
+        };
+        // relative locations are URI references, other schemes stay MRLs
+        let location = match mrl_to_path(&location) {
+            Some(path) => path,
+            None if is_mrl(&location) => location,
+            None => percent_decode(&location),
+        };
+        entries.push(PlaylistEntry {
+            location,
+            title: field("title").filter(|title| !title.is_empty()),
+            duration,
+        });
+    }
+    Ok(entries)
+}
+
+fn write_xspf(entries: &[PlaylistEntry]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n",
+    );
+    for entry in entries {
+        let location = if is_mrl(&entry.location) {
+            entry.location.clone()
+        } else if Path::new(&entry.location).is_absolute() {
+            path_to_file_uri(&entry.location)
+        } else {
+            percent_encode(&entry.location.replace('\\', "/"))
+        };
+        out.push_str("    <track>\n");
+        let _ = writeln!(out, "      <location>{}</location>", escape(&location));
+        if let Some(title) = &entry.title {
+            let _ = writeln!(out, "      <title>{}</title>", escape(title));
+        }
+        if let Some(duration) = entry.duration {
+            let _ = writeln!(out, "      <duration>{}</duration>", duration);
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+/// The contents of every `<name>` element in `xml` with the byte offsets they start
+/// at, not descending into matches.
+fn elements<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut pos = 0;
+    std::iter::from_fn(move || loop {
+        let start = pos + xml[pos..].find(&open)?;
+        let after = start + open.len();
+        pos = after;
+        // skip longer names sharing the prefix, e.g. <trackList> for <track>
+        match xml[after..].chars().next() {
+            Some('>') | Some('/') => {}
+            Some(c) if c.is_whitespace() => {}
+            _ => continue,
+        }
+        let tag_end = after + xml[after..].find('>')?;
+        if xml[..tag_end].ends_with('/') {
+            pos = tag_end + 1;
+            return Some((pos, ""));
+        }
+        let content_start = tag_end + 1;
+        let content_end = content_start + xml[content_start..].find(&close)?;
+        pos = content_end + close.len();
+        return Some((content_start, &xml[content_start..content_end]));
+    })
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest.find(';').map(|semi| &rest[1..semi]);
+        let decoded = match entity {
+            Some("amp") => Some('&'),
+            Some("lt") => Some('<'),
+            Some("gt") => Some('>'),
+            Some("quot") => Some('"'),
+            Some("apos") => Some('\''),
+            Some(e) if e.starts_with("#x") => u32::from_str_radix(&e[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            Some(e) if e.starts_with('#') => e[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match (decoded, entity) {
+            (Some(c), Some(entity)) => {
+                out.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
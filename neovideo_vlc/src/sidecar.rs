@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+/// Extensions of the subtitle formats looked for next to a media.
+pub const SUBTITLE_EXTENSIONS: [&str; 5] = ["srt", "ass", "ssa", "vtt", "sub"];
+
+pub fn is_subtitle_file<P: AsRef<Path>>(path: P) -> bool {
+    match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => SUBTITLE_EXTENSIONS
+            .iter()
+            .any(|sub| sub.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+/// Subtitle files in the directory of `media` that are named after it, such as
+/// `movie.srt` or `movie.en.srt` for `movie.mkv`.
+///
+/// The exact match comes first, the others are sorted by name.
+pub fn find_sidecar_subtitles<P: AsRef<Path>>(media: P) -> Vec<PathBuf> {
+    let media = media.as_ref();
+    let (dir, stem) = match (media.parent(), media.file_stem().and_then(|s| s.to_str())) {
+        (Some(dir), Some(stem)) => (dir, stem),
+        _ => return Vec::new(),
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut found: Vec<(bool, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.as_path() != media && is_subtitle_file(path) && path.is_file())
+        .filter_map(|path| {
+            let sub_stem = path.file_stem()?.to_str()?;
+            let exact = sub_stem == stem;
+            // movie.en.srt, movie.forced.srt
+            let tagged = matches!(sub_stem.strip_prefix(stem), Some(tag) if tag.starts_with('.'));
+            if exact || tagged {
+                Some((exact, path))
+            } else {
+                None
+            }
+        })
+        .collect();
+    found.sort_by(|(a_exact, a), (b_exact, b)| b_exact.cmp(a_exact).then_with(|| a.cmp(b)));
+    found.into_iter().map(|(_, path)| path).collect()
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use super::error::Result;
+use super::vlc::State;
+use super::vlcvideo::{VLCVideo, VideoFrame};
+
+/// Called on a decoding thread whenever a new frame is ready, e.g. to wake the event loop.
+pub type FrameNotifier = Box<dyn Fn() + Send>;
+
+/// Something that produces timed video frames and accepts transport commands.
+///
+/// Times are in milliseconds, positions in the range `0.0..=1.0`.
+pub trait VideoSource {
+    fn stop(&mut self);
+    fn pause(&mut self);
+    fn resume(&mut self);
+    fn toggle_pause(&mut self);
+    /// Shows the next frame and pauses.
+    fn next_frame(&mut self);
+    fn seek_time(&mut self, time: i64);
+    fn seek_position(&mut self, position: f32);
+    fn set_rate(&mut self, rate: f32) -> Result<()>;
+
+    fn rate(&self) -> f32;
+    fn time(&self) -> Option<i64>;
+    fn length(&self) -> Option<i64>;
+    fn position(&self) -> Option<f32>;
+    fn state(&self) -> State;
+    fn is_playing(&self) -> bool;
+    fn is_seekable(&self) -> bool;
+    fn can_pause(&self) -> bool;
+
+    /// Size of the produced pictures in pixels, `None` before the first one.
+    fn video_size(&self) -> Option<(u32, u32)>;
+    /// The latest frame, `None` before the first one. `update` tells whether it changed
+    /// since the last call.
+    fn get_video_frame(&mut self, update: &mut bool) -> Option<VideoFrame<'_>>;
+
+    /// Calls `notifier` for every new frame, for sources decoding on a thread of their
+    /// own. `None` removes the notifier.
+    fn set_frame_notifier(&mut self, _notifier: Option<FrameNotifier>) {}
+
+    /// How long until the next frame is due, for sources that produce frames only when
+    /// asked and thus never notify. `None` if no frame is coming.
+    fn next_frame_in(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Seeks `delta` milliseconds forward, or backward if negative.
+    fn seek_relative(&mut self, delta: i64) {
+        if let Some(time) = self.time() {
+            self.seek_time(time + delta);
+        }
+    }
+
+    /// Seeks by a fraction of the whole media, e.g. `0.1` skips a tenth forward.
+    fn seek_relative_position(&mut self, delta: f32) {
+        if let Some(position) = self.position() {
+            self.seek_position(position + delta);
+        }
+    }
+
+    /// The libvlc backend, for what only it supports such as media and audio.
+    fn vlc(&mut self) -> Option<&mut VLCVideo> {
+        None
+    }
+}
+
+impl VideoSource for VLCVideo {
+    fn stop(&mut self) {
+        VLCVideo::stop(self)
+    }
+
+    fn pause(&mut self) {
+        VLCVideo::pause(self)
+    }
+
+    fn resume(&mut self) {
+        VLCVideo::resume(self)
+    }
+
+    fn toggle_pause(&mut self) {
+        VLCVideo::toggle_pause(self)
+    }
+
+    fn next_frame(&mut self) {
+        VLCVideo::next_frame(self)
+    }
+
+    fn seek_time(&mut self, time: i64) {
+        VLCVideo::seek_time(self, time)
+    }
+
+    fn seek_position(&mut self, position: f32) {
+        VLCVideo::seek_position(self, position)
+    }
+
+    fn set_rate(&mut self, rate: f32) -> Result<()> {
+        VLCVideo::set_rate(self, rate)
+    }
+
+    fn rate(&self) -> f32 {
+        VLCVideo::rate(self)
+    }
+
+    fn time(&self) -> Option<i64> {
+        VLCVideo::time(self)
+    }
+
+    fn length(&self) -> Option<i64> {
+        VLCVideo::length(self)
+    }
+
+    fn position(&self) -> Option<f32> {
+        VLCVideo::position(self)
+    }
+
+    fn state(&self) -> State {
+        VLCVideo::state(self)
+    }
+
+    fn is_playing(&self) -> bool {
+        VLCVideo::is_playing(self)
+    }
+
+    fn is_seekable(&self) -> bool {
+        VLCVideo::is_seekable(self)
+    }
+
+    fn can_pause(&self) -> bool {
+        VLCVideo::can_pause(self)
+    }
+
+    fn video_size(&self) -> Option<(u32, u32)> {
+        VLCVideo::video_size(self)
+    }
+
+    fn get_video_frame(&mut self, update: &mut bool) -> Option<VideoFrame<'_>> {
+        VLCVideo::get_video_frame(self, update)
+    }
+
+    fn set_frame_notifier(&mut self, notifier: Option<FrameNotifier>) {
+        VLCVideo::set_frame_notifier(self, notifier)
+    }
+
+    fn seek_relative(&mut self, delta: i64) {
+        VLCVideo::seek_relative(self, delta)
+    }
+
+    fn seek_relative_position(&mut self, delta: f32) {
+        VLCVideo::seek_relative_position(self, delta)
+    }
+
+    fn vlc(&mut self) -> Option<&mut VLCVideo> {
+        Some(self)
+    }
+}
@@ -0,0 +1,508 @@
+use std::path::Path;
+
+use super::error::{NeovideoError, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    /// Guesses the format from the file extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<SubtitleFormat> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" => Some(SubtitleFormat::WebVtt),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the start of the file.
+    pub fn sniff(text: &str) -> SubtitleFormat {
+        if text.trim_start_matches('\u{feff}').starts_with("WEBVTT") {
+            SubtitleFormat::WebVtt
+        } else {
+            SubtitleFormat::Srt
+        }
+    }
+}
+
+/// A run of cue text with one style.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// RGB, `None` for the default colour.
+    pub color: Option<[u8; 3]>,
+}
+
+/// Horizontal alignment of the cue text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CueAlign {
+    Start,
+    #[default]
+    Center,
+    End,
+}
+
+/// Vertical placement of a cue.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CueLine {
+    /// Percent of the video height from the top.
+    Percent(f32),
+    /// Line number, counted from the top if positive and from the bottom if negative.
+    Number(i32),
+}
+
+/// Where a cue is shown, the defaults put it at the bottom centre.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CueSettings {
+    pub line: Option<CueLine>,
+    /// Percent of the video width the cue is anchored at.
+    pub position: Option<f32>,
+    /// Width of the cue box in percent of the video width.
+    pub size: Option<f32>,
+    pub align: CueAlign,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cue {
+    /// Start time in milliseconds.
+    pub start: i64,
+    /// End time in milliseconds, the cue is hidden from then on.
+    pub end: i64,
+    /// Lines of styled text.
+    pub lines: Vec<Vec<Span>>,
+    pub settings: CueSettings,
+}
+
+impl Cue {
+    /// The text without styling, lines separated by `\n`.
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|span| span.text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn is_active(&self, time: i64) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+/// A timeline of cues parsed from an SRT or WebVTT file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Subtitles {
+    /// Sorted by start time.
+    cues: Vec<Cue>,
+    /// Duration of the longest cue, bounds the search for active cues.
+    longest: i64,
+}
+
+impl Subtitles {
+    pub fn new(mut cues: Vec<Cue>) -> Subtitles {
+        cues.sort_by_key(|cue| cue.start);
+        let longest = cues
+            .iter()
+            .map(|cue| cue.end - cue.start)
+            .max()
+            .unwrap_or(0);
+        Subtitles { cues, longest }
+    }
+
+    pub fn parse(format: SubtitleFormat, text: &str) -> Result<Subtitles> {
+        let text = text.trim_start_matches('\u{feff}');
+        let cues = match format {
+            SubtitleFormat::Srt => parse_srt(text)?,
+            SubtitleFormat::WebVtt => parse_webvtt(text)?,
+        };
+        Ok(Subtitles::new(cues))
+    }
+
+    /// Reads an SRT or WebVTT file, by extension or by its contents.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Subtitles> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| NeovideoError::Io {
+            path: path.to_string_lossy().into_owned(),
+            reason: e.to_string(),
+        })?;
+        let text = String::from_utf8_lossy(&bytes);
+        let format =
+            SubtitleFormat::from_path(path).unwrap_or_else(|| SubtitleFormat::sniff(&text));
+        Subtitles::parse(format, &text)
+    }
+
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cues.is_empty()
+    }
+
+    /// The cues shown at `time` in milliseconds, in start order.
+    pub fn active(&self, time: i64) -> Vec<&Cue> {
+        let started = self.cues.partition_point(|cue| cue.start <= time);
+        let mut active: Vec<&Cue> = self.cues[..started]
+            .iter()
+            .rev()
+            .take_while(|cue| cue.start + self.longest > time)
+            .filter(|cue| cue.is_active(time))
+            .collect();
+        active.reverse();
+        active
+    }
+}
+
+fn parse_error(line: usize, reason: &str) -> NeovideoError {
+    NeovideoError::SubtitleParse {
+        line,
+        reason: reason.to_owned(),
+    }
+}
+
+/// Blank line separated blocks with the number of their first line.
+fn blocks(text: &str) -> Vec<(usize, Vec<&str>)> {
+    let mut blocks = Vec::new();
+    let mut block: Option<(usize, Vec<&str>)> = None;
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            blocks.extend(block.take());
+        } else {
+            block
+                .get_or_insert_with(|| (i + 1, Vec::new()))
+                .1
+                .push(line);
+        }
+    }
+    blocks.extend(block);
+    blocks
+}
+
+fn parse_srt(text: &str) -> Result<Vec<Cue>> {
+    let mut cues = Vec::new();
+    for (first, lines) in blocks(text) {
+        // the counter line is optional in practice
+        let timing = match lines.iter().position(|line| line.contains("-->")) {
+            Some(timing) if timing <= 1 => timing,
+            _ => continue,
+        };
+        let line = first + timing;
+        let (start, end, _) =
+            parse_timing(lines[timing]).ok_or_else(|| parse_error(line, "invalid timing"))?;
+        if end < start {
+            return Err(parse_error(line, "cue ends before it starts"));
+        }
+        let text = &lines[timing + 1..];
+        let mut settings = CueSettings::default();
+        // {\an8} and friends put the cue where the numpad key is
+        let an = text
+            .first()
+            .and_then(|line| line.trim_start().strip_prefix("{\\an"))
+            .and_then(|rest| rest.chars().next())
+            .and_then(|key| key.to_digit(10));
+        if let Some(key @ 1..=9) = an {
+            settings.line = match (key - 1) / 3 {
+                0 => None,
+                1 => Some(CueLine::Percent(50.0)),
+                _ => Some(CueLine::Percent(0.0)),
+            };
+            settings.align = match (key - 1) % 3 {
+                0 => CueAlign::Start,
+                1 => CueAlign::Center,
+                _ => CueAlign::End,
+            };
+        }
+        cues.push(Cue {
+            start,
+            end,
+            lines: parse_cue_text(text),
+            settings,
+        });
+    }
+    Ok(cues)
+}
+
+fn parse_webvtt(text: &str) -> Result<Vec<Cue>> {
+    let mut blocks = blocks(text).into_iter();
+    match blocks.next() {
+        Some((_, header)) if header[0].starts_with("WEBVTT") => {}
+        _ => return Err(parse_error(1, "missing WEBVTT header")),
+    }
+    let mut cues = Vec::new();
+    for (first, lines) in blocks {
+        if ["NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|keyword| lines[0].starts_with(keyword))
+        {
+            continue;
+        }
+        // an optional cue identifier comes first
+        let timing = match lines.iter().position(|line| line.contains("-->")) {
+            Some(timing) if timing <= 1 => timing,
+            _ => continue,
+        };
+        let line = first + timing;
+        let (start, end, rest) =
+            parse_timing(lines[timing]).ok_or_else(|| parse_error(line, "invalid timing"))?;
+        if end < start {
+            return Err(parse_error(line, "cue ends before it starts"));
+        }
+        cues.push(Cue {
+            start,
+            end,
+            lines: parse_cue_text(&lines[timing + 1..]),
+            settings: parse_settings(rest),
+        });
+    }
+    Ok(cues)
+}
+
+/// `start --> end` and whatever follows, times in milliseconds.
+fn parse_timing(line: &str) -> Option<(i64, i64, &str)> {
+    let (start, rest) = line.split_once("-->")?;
+    let rest = rest.trim_start();
+    let end_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let start = parse_timestamp(start.trim())?;
+    let end = parse_timestamp(&rest[..end_len])?;
+    Some((start, end, &rest[end_len..]))
+}
+
+/// `[hh:]mm:ss[,.]fff` in milliseconds.
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (clock, fraction) = match timestamp.rfind([',', '.']) {
+        Some(sep) => (&timestamp[..sep], &timestamp[sep + 1..]),
+        None => (timestamp, ""),
+    };
+    let mut seconds = 0;
+    let mut parts = 0;
+    for part in clock.split(':') {
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        seconds = seconds * 60 + part.parse::<i64>().ok()?;
+        parts += 1;
+    }
+    if !(2..=3).contains(&parts) || fraction.len() > 3 {
+        return None;
+    }
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // ",5" is half a second
+    let millis = format!("{:0<3}", fraction).parse::<i64>().ok()?;
+    Some(seconds * 1000 + millis)
+}
+
+/// WebVTT cue settings such as `line:10% position:50% align:start`.
+fn parse_settings(settings: &str) -> CueSettings {
+    let mut parsed = CueSettings::default();
+    for setting in settings.split_whitespace() {
+        let (key, value) = match setting.split_once(':') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        // line:0,start and position:10%,line-left carry an alignment we do not need
+        let value = value.split(',').next().unwrap_or(value);
+        match key {
+            "line" => {
+                parsed.line = match value.strip_suffix('%') {
+                    Some(percent) => percent.parse().ok().map(CueLine::Percent),
+                    None => value.parse().ok().map(CueLine::Number),
+                }
+            }
+            "position" => parsed.position = parse_percent(value),
+            "size" => parsed.size = parse_percent(value),
+            "align" => {
+                parsed.align = match value {
+                    "start" | "left" => CueAlign::Start,
+                    "end" | "right" => CueAlign::End,
+                    _ => CueAlign::Center,
+                }
+            }
+            _ => {}
+        }
+    }
+    parsed
+}
+
+fn parse_percent(value: &str) -> Option<f32> {
+    value.strip_suffix('%')?.parse().ok()
+}
+
+#[derive(Clone, Default)]
+struct Style {
+    bold: u32,
+    italic: u32,
+    underline: u32,
+    colors: Vec<Option<[u8; 3]>>,
+}
+
+impl Style {
+    fn span(&self) -> Span {
+        Span {
+            text: String::new(),
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            underline: self.underline > 0,
+            color: self.colors.last().copied().flatten(),
+        }
+    }
+}
+
+/// Splits cue text into styled spans, tags may span lines.
+fn parse_cue_text(lines: &[&str]) -> Vec<Vec<Span>> {
+    let mut style = Style::default();
+    let mut parsed = Vec::with_capacity(lines.len());
+    for line in lines {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut rest = *line;
+        while !rest.is_empty() {
+            if rest.starts_with('<') {
+                if let Some(end) = rest.find('>') {
+                    apply_tag(&mut style, &rest[1..end]);
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            } else if rest.starts_with("{\\") {
+                // ASS override tags in SRT files
+                if let Some(end) = rest.find('}') {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+            let (text, len) = match rest.strip_prefix('&').and_then(entity) {
+                Some((decoded, len)) => (decoded, len + 1),
+                None => {
+                    let c = rest.chars().next().unwrap_or_default();
+                    (Some(c), c.len_utf8())
+                }
+            };
+            rest = &rest[len..];
+            let c = match text {
+                Some(c) => c,
+                None => continue,
+            };
+            let span = style.span();
+            match spans.last_mut() {
+                Some(last)
+                    if (last.bold, last.italic, last.underline, last.color)
+                        == (span.bold, span.italic, span.underline, span.color) =>
+                {
+                    last.text.push(c)
+                }
+                _ => spans.push(Span {
+                    text: c.to_string(),
+                    ..span
+                }),
+            }
+        }
+        parsed.push(spans);
+    }
+    parsed
+}
+
+/// Decodes the entity at the start of `text`, after the `&`, returns the character
+/// if it is visible and the length up to and including the `;`.
+fn entity(text: &str) -> Option<(Option<char>, usize)> {
+    let end = text.find(';').filter(|&end| end <= 8)?;
+    let c = match &text[..end] {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        "lrm" | "rlm" => None,
+        _ => return None,
+    };
+    Some((c, end + 1))
+}
+
+fn apply_tag(style: &mut Style, tag: &str) {
+    let tag = tag.trim();
+    let (closing, tag) = match tag.strip_prefix('/') {
+        Some(tag) => (true, tag),
+        None => (false, tag),
+    };
+    // <c.yellow.bg_black>, <font color="red">, <v Speaker>
+    let name_len = tag
+        .find(|c: char| c == '.' || c.is_whitespace())
+        .unwrap_or(tag.len());
+    let name = tag[..name_len].to_ascii_lowercase();
+    let change = |count: &mut u32| {
+        if closing {
+            *count = count.saturating_sub(1);
+        } else {
+            *count += 1;
+        }
+    };
+    match name.as_str() {
+        "b" => change(&mut style.bold),
+        "i" => change(&mut style.italic),
+        "u" => change(&mut style.underline),
+        "font" | "c" if closing => {
+            style.colors.pop();
+        }
+        "font" => {
+            let color = attribute(tag, "color").and_then(parse_color);
+            let inherited = style.colors.last().copied().flatten();
+            style.colors.push(color.or(inherited));
+        }
+        "c" => {
+            let color = tag[name_len..].split('.').filter_map(parse_color).next();
+            let inherited = style.colors.last().copied().flatten();
+            style.colors.push(color.or(inherited));
+        }
+        // voices, languages, ruby and timestamps only affect the text around them
+        _ => {}
+    }
+}
+
+/// The value of `name="value"` in a tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{}=", name))? + name.len() + 1;
+    let value = &tag[start..];
+    match value.chars().next()? {
+        quote @ ('"' | '\'') => value[1..].split(quote).next(),
+        _ => value.split_whitespace().next(),
+    }
+}
+
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let color = color.trim().to_ascii_lowercase();
+    if let Some(hex) = color.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        return match digits.as_slice() {
+            [r, g, b] => Some([r * 17, g * 17, b * 17]),
+            [r1, r0, g1, g0, b1, b0] => Some([r1 * 16 + r0, g1 * 16 + g0, b1 * 16 + b0]),
+            _ => None,
+        };
+    }
+    // the WebVTT default classes
+    match color.as_str() {
+        "white" => Some([255, 255, 255]),
+        "black" => Some([0, 0, 0]),
+        "red" => Some([255, 0, 0]),
+        "lime" => Some([0, 255, 0]),
+        "green" => Some([0, 128, 0]),
+        "blue" => Some([0, 0, 255]),
+        "yellow" => Some([255, 255, 0]),
+        "cyan" => Some([0, 255, 255]),
+        "magenta" => Some([255, 0, 255]),
+        _ => None,
+    }
+}
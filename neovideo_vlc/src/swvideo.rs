@@ -0,0 +1,255 @@
+use std::sync::{Arc, Mutex};
+
+use libc::{c_char, c_uint, c_void};
+
+use super::media_player::MediaPlayer;
+use super::source::FrameNotifier;
+use super::tools::{ffi_guard, lock};
+use super::triple_buffer::{triple_buffer, Reader, Writer};
+use super::vlc::{libvlc_video_set_callbacks, libvlc_video_set_format_callbacks};
+
+/// Pixel layout of the frames delivered by the software output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chroma {
+    /// One plane, 4 bytes per pixel.
+    Rgba,
+    /// Planar YUV 4:2:0, a full size Y plane followed by half size U and V planes.
+    I420,
+}
+
+impl Chroma {
+    fn fourcc(self) -> &'static [u8; 4] {
+        match self {
+            Chroma::Rgba => b"RGBA",
+            Chroma::I420 => b"I420",
+        }
+    }
+}
+
+/// One plane of a decoded picture, `pitch` bytes per line.
+#[derive(Clone, Debug, Default)]
+pub struct Plane {
+    pub data: Vec<u8>,
+    pub pitch: usize,
+    pub lines: usize,
+}
+
+/// A decoded picture in CPU memory.
+///
+/// Pitches and line counts are padded, only the top left `width` by `height` pixels
+/// (halved for the chroma planes of I420) hold the picture.
+#[derive(Clone, Debug)]
+pub struct CpuFrame {
+    pub chroma: Chroma,
+    pub width: u32,
+    pub height: u32,
+    pub planes: Vec<Plane>,
+}
+
+impl CpuFrame {
+    pub(crate) fn new(chroma: Chroma, width: u32, height: u32) -> CpuFrame {
+        // libvlc wants pitches and line counts aligned to 32
+        let align = |n: u32| (n as usize + 31) & !31;
+        let planes = match chroma {
+            Chroma::Rgba => vec![(align(width * 4), align(height))],
+            Chroma::I420 => {
+                let (pitch, lines) = (align(width), align(height));
+                vec![
+                    (pitch, lines),
+                    (pitch / 2, lines / 2),
+                    (pitch / 2, lines / 2),
+                ]
+            }
+        };
+        CpuFrame {
+            chroma,
+            width,
+            height,
+            planes: planes
+                .into_iter()
+                .map(|(pitch, lines)| Plane {
+                    data: vec![0; pitch * lines],
+                    pitch,
+                    lines,
+                })
+                .collect(),
+        }
+    }
+
+    /// The picture as tightly packed RGBA rows, I420 is converted with BT.601 coefficients.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        match self.chroma {
+            Chroma::Rgba => {
+                let plane = &self.planes[0];
+                for row in plane.data.chunks(plane.pitch).take(height) {
+                    rgba.extend_from_slice(&row[..width * 4]);
+                }
+            }
+            Chroma::I420 => {
+                let (y, u, v) = (&self.planes[0], &self.planes[1], &self.planes[2]);
+                for row in 0..height {
+                    for col in 0..width {
+                        let luma = y.data[row * y.pitch + col] as f32 - 16.0;
+                        let chroma = (row / 2, col / 2);
+                        let cb = u.data[chroma.0 * u.pitch + chroma.1] as f32 - 128.0;
+                        let cr = v.data[chroma.0 * v.pitch + chroma.1] as f32 - 128.0;
+                        let to_u8 = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+                        rgba.extend_from_slice(&[
+                            to_u8(1.164 * luma + 1.596 * cr),
+                            to_u8(1.164 * luma - 0.392 * cb - 0.813 * cr),
+                            to_u8(1.164 * luma + 2.017 * cb),
+                            255,
+                        ]);
+                    }
+                }
+            }
+        }
+        rgba
+    }
+}
+
+/// Frames decoded by libvlc into CPU buffers, triple buffered like the textures of the
+/// OpenGL output.
+pub(crate) struct SoftwareOutput {
+    reader: Reader<Option<CpuFrame>>,
+    decoder: Arc<Decoder>,
+}
+
+/// The side of the software output used by the libvlc callbacks. libvlc is handed the
+/// address of the `Arc` contents, which stays put however the output moves.
+struct Decoder {
+    chroma: Chroma,
+    state: Mutex<DecoderState>,
+    frame_notifier: Mutex<Option<FrameNotifier>>,
+}
+
+struct DecoderState {
+    /// Size set by the last `format`, `None` before the first stream. It is kept after
+    /// `cleanup` for pictures libvlc may still lock.
+    size: Option<(u32, u32)>,
+    /// Frames are allocated here as needed.
+    writer: Writer<Option<CpuFrame>>,
+}
+
+impl SoftwareOutput {
+    pub(crate) fn new(chroma: Chroma) -> SoftwareOutput {
+        let (writer, reader) = triple_buffer([None, None, None]);
+        SoftwareOutput {
+            reader,
+            decoder: Arc::new(Decoder {
+                chroma,
+                state: Mutex::new(DecoderState { size: None, writer }),
+                frame_notifier: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub(crate) fn set_frame_notifier(&mut self, notifier: Option<FrameNotifier>) {
+        *lock(&self.decoder.frame_notifier) = notifier;
+    }
+
+    /// Routes the video of `player` into this output, the player keeps the decoder alive.
+    pub(crate) fn attach(&mut self, player: &MediaPlayer) {
+        player.keep_alive(self.decoder.clone());
+        let opaque = Arc::as_ptr(&self.decoder) as *mut c_void;
+        unsafe {
+            libvlc_video_set_callbacks(
+                player.raw(),
+                Some(Decoder::lock),
+                None,
+                Some(Decoder::display),
+                opaque,
+            );
+            libvlc_video_set_format_callbacks(
+                player.raw(),
+                Some(Decoder::format),
+                Some(Decoder::cleanup),
+            );
+        }
+    }
+
+    /// The latest displayed frame, `update` tells whether it changed since the last call.
+    pub(crate) fn get_video_frame(&mut self, update: &mut bool) -> Option<&CpuFrame> {
+        *update = self.reader.update();
+        self.reader.buffer().as_ref()
+    }
+
+    pub(crate) fn video_size(&self) -> Option<(u32, u32)> {
+        self.reader
+            .buffer()
+            .as_ref()
+            .map(|frame| (frame.width, frame.height))
+    }
+}
+
+impl Decoder {
+    unsafe extern "C" fn format(
+        opaque: *mut *mut c_void,
+        chroma: *mut c_char,
+        width: *mut c_uint,
+        height: *mut c_uint,
+        pitches: *mut c_uint,
+        lines: *mut c_uint,
+    ) -> c_uint {
+        ffi_guard(0, || {
+            let that = &*(*opaque as *const Decoder);
+            let mut state = lock(&that.state);
+            // libvlc converts whatever it decodes to the chroma asked for here
+            std::ptr::copy_nonoverlapping(
+                that.chroma.fourcc().as_ptr() as *const c_char,
+                chroma,
+                4,
+            );
+            let frame = CpuFrame::new(that.chroma, *width, *height);
+            for (i, plane) in frame.planes.iter().enumerate() {
+                *pitches.add(i) = plane.pitch as c_uint;
+                *lines.add(i) = plane.lines as c_uint;
+            }
+            state.size = Some((*width, *height));
+            *state.writer.buffer() = Some(frame);
+            1
+        })
+    }
+
+    unsafe extern "C" fn cleanup(opaque: *mut c_void) {
+        ffi_guard((), || {
+            let that = &*(opaque as *const Decoder);
+            let mut state = lock(&that.state);
+            // the reader drops the last picture of the stream on its next update
+            *state.writer.buffer() = None;
+            state.writer.publish();
+        })
+    }
+
+    unsafe extern "C" fn lock(opaque: *mut c_void, planes: *mut *mut c_void) -> *mut c_void {
+        ffi_guard(std::ptr::null_mut(), || {
+            let that = &*(opaque as *const Decoder);
+            let mut state = lock(&that.state);
+            // libvlc calls `format` before locking any picture
+            let (width, height) = state.size.unwrap_or((0, 0));
+            let buffer = state.writer.buffer();
+            // buffers handed back by the reader may be empty or of an earlier stream
+            let frame = match buffer {
+                Some(frame) if (frame.width, frame.height) == (width, height) => frame,
+                _ => buffer.insert(CpuFrame::new(that.chroma, width, height)),
+            };
+            // the writer's buffer is only touched on this thread until `display`
+            for (i, plane) in frame.planes.iter_mut().enumerate() {
+                *planes.add(i) = plane.data.as_mut_ptr() as *mut c_void;
+            }
+            std::ptr::null_mut()
+        })
+    }
+
+    unsafe extern "C" fn display(opaque: *mut c_void, _picture: *mut c_void) {
+        ffi_guard((), || {
+            let that = &*(opaque as *const Decoder);
+            lock(&that.state).writer.publish();
+            if let Some(notify) = &*lock(&that.frame_notifier) {
+                notify();
+            }
+        })
+    }
+}
@@ -0,0 +1,263 @@
+use std::time::{Duration, Instant};
+
+use super::error::{NeovideoError, Result};
+use super::source::VideoSource;
+use super::swvideo::{Chroma, CpuFrame};
+use super::vlc::State;
+use super::vlcvideo::VideoFrame;
+
+/// 75% colour bars, left to right.
+const BARS: [[u8; 3]; 7] = [
+    [191, 191, 191],
+    [191, 191, 0],
+    [0, 191, 191],
+    [0, 191, 0],
+    [191, 0, 191],
+    [191, 0, 0],
+    [0, 0, 191],
+];
+
+/// 3x5 glyphs of the digits, one bit per pixel, row by row from the top.
+const DIGITS: [u16; 10] = [
+    0b111_101_101_101_111,
+    0b010_110_010_010_111,
+    0b111_001_111_100_111,
+    0b111_001_111_001_111,
+    0b101_101_111_001_001,
+    0b111_100_111_001_111,
+    0b111_100_111_101_111,
+    0b111_001_001_001_001,
+    0b111_101_111_101_111,
+    0b111_101_111_001_111,
+];
+
+/// A synthetic source drawing colour bars and the frame number, for running without
+/// libvlc or media files.
+///
+/// Frame `n` is shown from `n * 1000 / fps` milliseconds on and its pixels only depend on
+/// `n` and the size, so seeking and stepping give reproducible pictures.
+pub struct TestPatternSource {
+    width: u32,
+    height: u32,
+    fps: f32,
+    length: Option<i64>,
+    rate: f32,
+    state: State,
+    /// Media time when the clock last (re)started.
+    base_time: i64,
+    /// Set while playing, the media time is `base_time` plus the time elapsed since.
+    started: Option<Instant>,
+    frame: CpuFrame,
+    /// Index of the frame held by `frame`.
+    rendered: Option<u64>,
+}
+
+impl TestPatternSource {
+    /// A playing source of `width` by `height` pixels at `fps` frames per second, with
+    /// no end.
+    pub fn new(width: u32, height: u32, fps: f32) -> TestPatternSource {
+        TestPatternSource {
+            width: width.max(1),
+            height: height.max(1),
+            fps: if fps > 0.0 { fps } else { 25.0 },
+            length: None,
+            rate: 1.0,
+            state: State::Playing,
+            base_time: 0,
+            started: Some(Instant::now()),
+            frame: CpuFrame::new(Chroma::Rgba, width.max(1), height.max(1)),
+            rendered: None,
+        }
+    }
+
+    /// Ends playback after `length` milliseconds.
+    pub fn with_length(mut self, length: i64) -> TestPatternSource {
+        self.length = Some(length.max(0));
+        self
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    /// Index of the frame shown at the current time.
+    pub fn frame_index(&self) -> u64 {
+        (self.time().unwrap_or(0) as f64 * self.fps as f64 / 1000.0) as u64
+    }
+
+    /// Draws frame `index` into `frame`, which must be an RGBA frame.
+    pub fn draw(frame: &mut CpuFrame, index: u64) {
+        let (width, height) = (frame.width as usize, frame.height as usize);
+        let plane = &mut frame.planes[0];
+        let pitch = plane.pitch;
+        let bars_height = height * 2 / 3;
+        for (y, row) in plane.data.chunks_mut(pitch).take(height).enumerate() {
+            for x in 0..width {
+                let rgb = if y < bars_height {
+                    BARS[x * BARS.len() / width]
+                } else {
+                    [0, 0, 0]
+                };
+                row[x * 4..x * 4 + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+            }
+        }
+
+        // the frame number in white below the bars
+        let scale = (height / 30).max(1);
+        let mut left = scale * 2;
+        let top = bars_height + scale * 2;
+        for digit in index
+            .to_string()
+            .bytes()
+            .map(|b| DIGITS[(b - b'0') as usize])
+        {
+            for cell in 0..15 {
+                if digit >> (14 - cell) & 1 == 0 {
+                    continue;
+                }
+                let (cx, cy) = (left + cell % 3 * scale, top + cell / 3 * scale);
+                for y in cy..(cy + scale).min(height) {
+                    for x in cx..(cx + scale).min(width) {
+                        let offset = y * pitch + x * 4;
+                        plane.data[offset..offset + 4].copy_from_slice(&[255; 4]);
+                    }
+                }
+            }
+            left += scale * 4;
+        }
+    }
+
+    fn restart_clock(&mut self, time: i64) {
+        self.base_time = time;
+        self.started = if self.state == State::Playing {
+            Some(Instant::now())
+        } else {
+            None
+        };
+    }
+}
+
+impl VideoSource for TestPatternSource {
+    fn stop(&mut self) {
+        self.state = State::Stopped;
+        self.restart_clock(0);
+    }
+
+    fn pause(&mut self) {
+        let time = self.time().unwrap_or(0);
+        self.state = State::Paused;
+        self.restart_clock(time);
+    }
+
+    fn resume(&mut self) {
+        let time = self.time().unwrap_or(0);
+        self.state = State::Playing;
+        self.restart_clock(time);
+    }
+
+    fn toggle_pause(&mut self) {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.resume();
+        }
+    }
+
+    fn next_frame(&mut self) {
+        self.pause();
+        let next = self.frame_index() + 1;
+        self.seek_time((next as f64 * 1000.0 / self.fps as f64).ceil() as i64);
+    }
+
+    fn seek_time(&mut self, time: i64) {
+        let time = match self.length {
+            Some(length) => time.clamp(0, length),
+            None => time.max(0),
+        };
+        self.restart_clock(time);
+    }
+
+    fn seek_position(&mut self, position: f32) {
+        if let Some(length) = self.length {
+            self.seek_time((position.clamp(0.0, 1.0) as f64 * length as f64) as i64);
+        }
+    }
+
+    fn set_rate(&mut self, rate: f32) -> Result<()> {
+        if rate <= 0.0 || !rate.is_finite() {
+            return Err(NeovideoError::Vlc("playback rate not supported".to_owned()));
+        }
+        let time = self.time().unwrap_or(0);
+        self.rate = rate;
+        self.restart_clock(time);
+        Ok(())
+    }
+
+    fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    fn time(&self) -> Option<i64> {
+        let elapsed = self.started.map_or(0, |started| {
+            (started.elapsed().as_millis() as f32 * self.rate) as i64
+        });
+        let time = self.base_time + elapsed;
+        Some(match self.length {
+            Some(length) => time.min(length),
+            None => time,
+        })
+    }
+
+    fn length(&self) -> Option<i64> {
+        self.length
+    }
+
+    fn position(&self) -> Option<f32> {
+        match (self.time(), self.length) {
+            (Some(time), Some(length)) if length > 0 => Some(time as f32 / length as f32),
+            _ => None,
+        }
+    }
+
+    fn state(&self) -> State {
+        match (self.state, self.time(), self.length) {
+            (State::Playing, Some(time), Some(length)) if time >= length => State::Ended,
+            (state, _, _) => state,
+        }
+    }
+
+    fn is_playing(&self) -> bool {
+        self.state() == State::Playing
+    }
+
+    fn is_seekable(&self) -> bool {
+        self.length.is_some()
+    }
+
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    fn video_size(&self) -> Option<(u32, u32)> {
+        Some((self.width, self.height))
+    }
+
+    fn get_video_frame(&mut self, update: &mut bool) -> Option<VideoFrame<'_>> {
+        let index = self.frame_index();
+        *update = self.rendered != Some(index);
+        if *update {
+            TestPatternSource::draw(&mut self.frame, index);
+            self.rendered = Some(index);
+        }
+        Some(VideoFrame::Cpu(&self.frame))
+    }
+
+    fn next_frame_in(&self) -> Option<Duration> {
+        if !self.is_playing() {
+            return None;
+        }
+        let next = (self.frame_index() + 1) as f64 * 1000.0 / self.fps as f64;
+        let wait = (next - self.time().unwrap_or(0) as f64) / self.rate as f64;
+        Some(Duration::from_secs_f64(wait.max(0.0) / 1000.0))
+    }
+}
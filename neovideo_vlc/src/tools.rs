@@ -0,0 +1,108 @@
+use std::ffi::{CStr, CString};
+use std::fmt::Write as _;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use libc::{c_char, c_void};
+
+use super::vlc::libvlc_free;
+
+/// Runs the body of a callback called by libvlc. Unwinding into C is undefined
+/// behaviour, so a panic returns `default` to libvlc instead.
+pub(crate) fn ffi_guard<R>(default: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+/// Locks a mutex shared with libvlc callbacks, even if a panic caught by `ffi_guard`
+/// poisoned it. Losing the frame or buffer that panicked is better than losing the
+/// output for the rest of the session.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn to_cstr(s: &str) -> Option<CString> {
+    CString::new(s).ok()
+}
+
+pub(crate) fn path_to_cstr(path: &Path) -> Option<CString> {
+    to_cstr(path.to_str()?)
+}
+
+/// Copies a string owned by libvlc and frees the original with `libvlc_free`.
+pub(crate) unsafe fn from_cstr_owned(p: *mut c_char) -> Option<String> {
+    if p.is_null() {
+        return None;
+    }
+    let s = CStr::from_ptr(p).to_string_lossy().into_owned();
+    libvlc_free(p as *mut c_void);
+    Some(s)
+}
+
+/// Copies a string that stays owned by libvlc.
+pub(crate) unsafe fn from_cstr_ref(p: *const c_char) -> Option<String> {
+    if p.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(p).to_string_lossy().into_owned())
+}
+
+/// A `file://` URI for a local path, relative paths are taken from the working directory.
+pub(crate) fn path_to_uri_cstr(path: &Path) -> Option<CString> {
+    let absolute;
+    let path = if path.is_absolute() {
+        path
+    } else {
+        absolute = std::env::current_dir().ok()?.join(path);
+        &absolute
+    };
+    to_cstr(&path_to_file_uri(path.to_str()?))
+}
+
+/// A `file://` URI for an absolute local path.
+pub(crate) fn path_to_file_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    // file:///C:/video.mkv
+    let slash = if path.starts_with('/') { "" } else { "/" };
+    format!("file://{}{}", slash, percent_encode(&path))
+}
+
+/// Escapes everything but unreserved characters and path separators.
+pub(crate) fn percent_encode(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                out.push(b as char)
+            }
+            b => {
+                let _ = write!(out, "%{:02X}", b);
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let escaped = match tail {
+            [h, l, ..] if b == b'%' => std::str::from_utf8(&[*h, *l])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(b);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
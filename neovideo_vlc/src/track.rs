@@ -0,0 +1,185 @@
+use std::slice;
+
+use super::tools::from_cstr_ref;
+use super::vlc::{
+    libvlc_media_get_codec_description, libvlc_media_t, libvlc_media_track_t,
+    libvlc_media_tracks_get, libvlc_media_tracks_release, libvlc_track_description_list_release,
+    libvlc_track_description_t, TrackType,
+};
+
+/// Type specific properties of a track.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrackDetails {
+    Audio {
+        channels: u32,
+        /// Sample rate in Hz.
+        rate: u32,
+    },
+    Video {
+        width: u32,
+        height: u32,
+        /// Sample aspect ratio as numerator and denominator.
+        sar: (u32, u32),
+        /// Frames per second, `None` if unknown.
+        frame_rate: Option<f32>,
+    },
+    Text {
+        encoding: Option<String>,
+    },
+    Unknown,
+}
+
+/// An elementary stream of a media, as found by parsing or playback.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track {
+    /// The id to select the track with.
+    pub id: i32,
+    pub track_type: TrackType,
+    /// The fourcc, such as `h264`.
+    pub codec: String,
+    /// A readable codec name, such as `H264 - MPEG-4 AVC (part 10)`.
+    pub codec_name: Option<String>,
+    pub language: Option<String>,
+    pub description: Option<String>,
+    /// Bits per second, `None` if unknown.
+    pub bitrate: Option<u32>,
+    pub details: TrackDetails,
+}
+
+impl Track {
+    /// The tracks of `media`, empty until it is parsed or playing.
+    ///
+    /// # Safety
+    /// `media` must be a valid media.
+    pub(crate) unsafe fn list(media: *mut libvlc_media_t) -> Vec<Track> {
+        let mut tracks = std::ptr::null_mut();
+        let count = libvlc_media_tracks_get(media, &mut tracks);
+        if tracks.is_null() {
+            return Vec::new();
+        }
+        let list = slice::from_raw_parts(tracks, count as usize)
+            .iter()
+            .filter(|track| !track.is_null())
+            .map(|&track| Track::from_raw(&*track))
+            .collect();
+        libvlc_media_tracks_release(tracks, count);
+        list
+    }
+
+    unsafe fn from_raw(track: &libvlc_media_track_t) -> Track {
+        let details = match track.i_type {
+            TrackType::Audio if !track.audio().is_null() => {
+                let audio = &*track.audio();
+                TrackDetails::Audio {
+                    channels: audio.i_channels,
+                    rate: audio.i_rate,
+                }
+            }
+            TrackType::Video if !track.video().is_null() => {
+                let video = &*track.video();
+                TrackDetails::Video {
+                    width: video.i_width,
+                    height: video.i_height,
+                    sar: (video.i_sar_num, video.i_sar_den),
+                    frame_rate: if video.i_frame_rate_den == 0 || video.i_frame_rate_num == 0 {
+                        None
+                    } else {
+                        Some(video.i_frame_rate_num as f32 / video.i_frame_rate_den as f32)
+                    },
+                }
+            }
+            TrackType::Text if !track.subtitle().is_null() => TrackDetails::Text {
+                encoding: from_cstr_ref((*track.subtitle()).psz_encoding),
+            },
+            _ => TrackDetails::Unknown,
+        };
+        Track {
+            id: track.i_id,
+            track_type: track.i_type,
+            codec: fourcc_to_string(track.i_codec),
+            codec_name: from_cstr_ref(libvlc_media_get_codec_description(
+                track.i_type,
+                track.i_codec,
+            ))
+            .filter(|name| !name.is_empty()),
+            language: from_cstr_ref(track.psz_language).filter(|s| !s.is_empty()),
+            description: from_cstr_ref(track.psz_description).filter(|s| !s.is_empty()),
+            bitrate: if track.i_bitrate == 0 {
+                None
+            } else {
+                Some(track.i_bitrate)
+            },
+            details,
+        }
+    }
+
+    /// A short summary such as `h264 1920x1080 25 fps` or `mp4a 2 ch 48000 Hz [eng]`.
+    pub fn summary(&self) -> String {
+        let mut summary = self.codec.clone();
+        match &self.details {
+            TrackDetails::Audio { channels, rate } => {
+                summary += &format!(" {} ch {} Hz", channels, rate);
+            }
+            TrackDetails::Video {
+                width,
+                height,
+                frame_rate,
+                ..
+            } => {
+                summary += &format!(" {}x{}", width, height);
+                if let Some(fps) = frame_rate {
+                    let fps = format!("{:.3}", fps);
+                    summary += &format!(" {} fps", fps.trim_end_matches('0').trim_end_matches('.'));
+                }
+            }
+            TrackDetails::Text { .. } | TrackDetails::Unknown => {}
+        }
+        if let Some(bitrate) = self.bitrate {
+            summary += &format!(" {} kb/s", bitrate / 1000);
+        }
+        if let Some(language) = &self.language {
+            summary += &format!(" [{}]", language);
+        }
+        summary
+    }
+}
+
+/// A selectable track of the playing media, as the player names it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackDescription {
+    /// The id to select the track with, `-1` for the entry that disables the type.
+    pub id: i32,
+    pub name: String,
+}
+
+impl TrackDescription {
+    /// Copies and releases a description list returned by libvlc.
+    ///
+    /// # Safety
+    /// `list` must be null or a list returned by libvlc that is not released elsewhere.
+    pub(crate) unsafe fn from_list(list: *mut libvlc_track_description_t) -> Vec<TrackDescription> {
+        let mut descriptions = Vec::new();
+        let mut item = list;
+        while !item.is_null() {
+            descriptions.push(TrackDescription {
+                id: (*item).i_id,
+                name: from_cstr_ref((*item).psz_name).unwrap_or_else(|| (*item).i_id.to_string()),
+            });
+            item = (*item).p_next;
+        }
+        if !list.is_null() {
+            libvlc_track_description_list_release(list);
+        }
+        descriptions
+    }
+}
+
+fn fourcc_to_string(fourcc: u32) -> String {
+    fourcc
+        .to_le_bytes()
+        .iter()
+        .map(|&b| if b.is_ascii_graphic() { b as char } else { ' ' })
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
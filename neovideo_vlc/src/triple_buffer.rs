@@ -0,0 +1,113 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// Set in `TripleBuffer::back` while the back buffer holds a frame the reader has not
+/// taken yet.
+const FRESH: u8 = 0b100;
+const INDEX: u8 = 0b011;
+
+/// Three buffers handed between one writer and one reader without locks: the writer
+/// fills one, the reader holds one and the third is the latest complete frame.
+struct TripleBuffer<T> {
+    buffers: [UnsafeCell<T>; 3],
+    /// Index of the back buffer, along with `FRESH`.
+    back: AtomicU8,
+}
+
+/// Creates a triple buffer, the writer starts with `buffers[0]` and the reader with
+/// `buffers[2]`, both may move to another thread.
+pub fn triple_buffer<T: Send>(buffers: [T; 3]) -> (Writer<T>, Reader<T>) {
+    let shared = Arc::new(TripleBuffer {
+        buffers: buffers.map(UnsafeCell::new),
+        back: AtomicU8::new(1),
+    });
+    let writer = Writer {
+        shared: shared.clone(),
+        index: 0,
+    };
+    let reader = Reader { shared, index: 2 };
+    (writer, reader)
+}
+
+/// The producing side of a triple buffer.
+pub struct Writer<T> {
+    shared: Arc<TripleBuffer<T>>,
+    index: u8,
+}
+
+// every buffer is reachable by one side at a time, handing it over synchronises
+unsafe impl<T: Send> Send for Writer<T> {}
+
+impl<T> Writer<T> {
+    /// The buffer being written, nobody else can see it until `publish`.
+    pub fn buffer(&mut self) -> &mut T {
+        unsafe { &mut *self.shared.buffers[self.index as usize].get() }
+    }
+
+    /// Hands the written buffer to the reader and takes the back buffer in exchange,
+    /// replacing any frame the reader has not taken yet.
+    pub fn publish(&mut self) {
+        let back = self.shared.back.swap(self.index | FRESH, Ordering::AcqRel);
+        self.index = back & INDEX;
+    }
+
+    /// All three buffers, writer's first and reader's last. Taking both halves proves
+    /// that nobody else uses them, e.g. to free what they hold.
+    ///
+    /// Panics if `reader` belongs to another triple buffer.
+    pub fn buffers_mut<'a>(&'a mut self, reader: &'a mut Reader<T>) -> [&'a mut T; 3] {
+        assert!(Arc::ptr_eq(&self.shared, &reader.shared));
+        let back = self.shared.back.load(Ordering::Acquire) & INDEX;
+        [self.index, back, reader.index]
+            .map(|index| unsafe { &mut *self.shared.buffers[index as usize].get() })
+    }
+}
+
+/// The consuming side of a triple buffer.
+///
+/// A shared reader hands out the held buffer on every thread it is shared with, so it
+/// is only `Sync` for `Sync` contents:
+///
+/// ```compile_fail
+/// use std::cell::Cell;
+/// use neovideo_vlc::triple_buffer::Reader;
+///
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<Reader<Cell<u8>>>();
+/// ```
+pub struct Reader<T> {
+    shared: Arc<TripleBuffer<T>>,
+    index: u8,
+}
+
+unsafe impl<T: Send> Send for Reader<T> {}
+unsafe impl<T: Send + Sync> Sync for Reader<T> {}
+
+impl<T> Reader<T> {
+    /// Whether a frame newer than the one held was published.
+    pub fn has_update(&self) -> bool {
+        self.shared.back.load(Ordering::Relaxed) & FRESH != 0
+    }
+
+    /// Moves on to the latest published frame, returns `false` if there is none newer
+    /// and keeps the held buffer then.
+    pub fn update(&mut self) -> bool {
+        if !self.has_update() {
+            return false;
+        }
+        // only the reader clears FRESH, so the swap takes the frame seen above or a newer one
+        let back = self.shared.back.swap(self.index, Ordering::AcqRel);
+        self.index = back & INDEX;
+        true
+    }
+
+    /// The held buffer, it does not change until `update`.
+    pub fn buffer(&self) -> &T {
+        unsafe { &*self.shared.buffers[self.index as usize].get() }
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.shared.buffers[self.index as usize].get() }
+    }
+}
@@ -44,6 +44,67 @@ pub enum Meta {
     Actors,
 }
 
+impl Meta {
+    /// The meta for a raw `libvlc_meta_t`, `None` for values unknown to this crate.
+    pub fn from_raw(meta: i32) -> Option<Meta> {
+        Meta::ALL.into_iter().find(|m| *m as i32 == meta)
+    }
+
+    pub const ALL: [Meta; 23] = [
+        Meta::Title,
+        Meta::Artist,
+        Meta::Genre,
+        Meta::Copyright,
+        Meta::Album,
+        Meta::TrackNumber,
+        Meta::Description,
+        Meta::Rating,
+        Meta::Date,
+        Meta::Setting,
+        Meta::URL,
+        Meta::Language,
+        Meta::NowPlaying,
+        Meta::Publisher,
+        Meta::EncodedBy,
+        Meta::ArtworkURL,
+        Meta::TrackID,
+        Meta::TrackTotal,
+        Meta::Director,
+        Meta::Season,
+        Meta::Episode,
+        Meta::ShowName,
+        Meta::Actors,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Meta::Title => "Title",
+            Meta::Artist => "Artist",
+            Meta::Genre => "Genre",
+            Meta::Copyright => "Copyright",
+            Meta::Album => "Album",
+            Meta::TrackNumber => "Track number",
+            Meta::Description => "Description",
+            Meta::Rating => "Rating",
+            Meta::Date => "Date",
+            Meta::Setting => "Setting",
+            Meta::URL => "URL",
+            Meta::Language => "Language",
+            Meta::NowPlaying => "Now playing",
+            Meta::Publisher => "Publisher",
+            Meta::EncodedBy => "Encoded by",
+            Meta::ArtworkURL => "Artwork URL",
+            Meta::TrackID => "Track ID",
+            Meta::TrackTotal => "Track total",
+            Meta::Director => "Director",
+            Meta::Season => "Season",
+            Meta::Episode => "Episode",
+            Meta::ShowName => "Show name",
+            Meta::Actors => "Actors",
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum State {
@@ -57,6 +118,23 @@ pub enum State {
     Error,
 }
 
+impl State {
+    /// The state for a raw `libvlc_state_t`, `None` for values unknown to this crate.
+    pub fn from_raw(state: i32) -> Option<State> {
+        match state {
+            0 => Some(State::NothingSpecial),
+            1 => Some(State::Opening),
+            2 => Some(State::Buffering),
+            3 => Some(State::Playing),
+            4 => Some(State::Paused),
+            5 => Some(State::Stopped),
+            6 => Some(State::Ended),
+            7 => Some(State::Error),
+            _ => None,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum TrackType {
@@ -66,6 +144,41 @@ pub enum TrackType {
     Text = 2,
 }
 
+/// Stereo mode of the audio output, the safe counterpart of `libvlc_audio_output_channel_t`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AudioChannel {
+    Stereo = 1,
+    ReverseStereo = 2,
+    Left = 3,
+    Right = 4,
+    Dolby = 5,
+}
+
+impl AudioChannel {
+    pub const ALL: [AudioChannel; 5] = [
+        AudioChannel::Stereo,
+        AudioChannel::ReverseStereo,
+        AudioChannel::Left,
+        AudioChannel::Right,
+        AudioChannel::Dolby,
+    ];
+
+    pub fn from_raw(channel: i32) -> Option<AudioChannel> {
+        AudioChannel::ALL.into_iter().find(|c| *c as i32 == channel)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioChannel::Stereo => "Stereo",
+            AudioChannel::ReverseStereo => "Reverse stereo",
+            AudioChannel::Left => "Left",
+            AudioChannel::Right => "Right",
+            AudioChannel::Dolby => "Dolby Surround",
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Position {
@@ -241,6 +354,12 @@ extern "C" {
         f_callback: libvlc_callback_t,
         user_data: *mut c_void,
     ) -> c_int;
+    pub fn libvlc_event_detach(
+        p_event_manager: *mut libvlc_event_manager_t,
+        i_event_type: libvlc_event_type_t,
+        f_callback: libvlc_callback_t,
+        p_user_data: *mut c_void,
+    );
     pub fn libvlc_event_type_name(event_type: libvlc_event_type_t) -> *const c_char;
     pub fn libvlc_log_get_context(
         ctx: *const libvlc_log_t,
@@ -282,6 +401,21 @@ pub const libvlc_media_option_unique: u32 = 0x100;
 
 pub use TrackType as libvlc_track_type_t;
 
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum libvlc_media_slave_type_t {
+    libvlc_media_slave_type_subtitle = 0,
+    libvlc_media_slave_type_audio,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct libvlc_media_slave_t {
+    pub psz_uri: *mut c_char,
+    pub i_type: libvlc_media_slave_type_t,
+    pub i_priority: c_uint,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct libvlc_media_stats_t {
@@ -473,6 +607,21 @@ extern "C" {
         tracks: *mut *mut *mut libvlc_media_track_t,
     ) -> c_uint;
     pub fn libvlc_media_tracks_release(p_tracks: *mut *mut libvlc_media_track_t, i_count: c_uint);
+    pub fn libvlc_media_get_codec_description(
+        i_type: libvlc_track_type_t,
+        i_codec: u32,
+    ) -> *const c_char;
+    pub fn libvlc_media_slaves_add(
+        p_md: *mut libvlc_media_t,
+        i_type: libvlc_media_slave_type_t,
+        i_priority: c_uint,
+        psz_uri: *const c_char,
+    ) -> c_int;
+    pub fn libvlc_media_slaves_get(
+        p_md: *mut libvlc_media_t,
+        ppp_slaves: *mut *mut *mut libvlc_media_slave_t,
+    ) -> c_uint;
+    pub fn libvlc_media_slaves_release(pp_slaves: *mut *mut libvlc_media_slave_t, i_count: c_uint);
 }
 
 // From libvlc_media_player.h
@@ -723,7 +872,7 @@ pub enum libvlc_video_metadata_type_t {
 }
 
 pub type libvlc_video_lock_cb =
-    Option<unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void>;
+    Option<unsafe extern "C" fn(*mut c_void, *mut *mut c_void) -> *mut c_void>;
 pub type libvlc_video_unlock_cb =
     Option<unsafe extern "C" fn(*mut c_void, *mut c_void, *const *mut c_void)>;
 pub type libvlc_video_display_cb = Option<unsafe extern "C" fn(*mut c_void, *mut c_void)>;
@@ -735,7 +884,7 @@ pub type libvlc_video_format_cb = Option<
         *mut c_uint,
         *mut c_uint,
         *mut c_uint,
-    ),
+    ) -> c_uint,
 >;
 pub type libvlc_video_cleanup_cb = Option<unsafe extern "C" fn(*mut c_void)>;
 pub type libvlc_audio_play_cb =
@@ -746,7 +895,7 @@ pub type libvlc_audio_flush_cb = Option<unsafe extern "C" fn(*mut c_void, i64)>;
 pub type libvlc_audio_drain_cb = Option<unsafe extern "C" fn(*mut c_void)>;
 pub type libvlc_audio_set_volume_cb = Option<unsafe extern "C" fn(*mut c_void, c_float, c_bool)>;
 pub type libvlc_audio_setup_cb =
-    Option<unsafe extern "C" fn(*mut *mut c_void, *mut c_char, *mut c_uint, *mut c_uint)>;
+    Option<unsafe extern "C" fn(*mut *mut c_void, *mut c_char, *mut c_uint, *mut c_uint) -> c_int>;
 
 pub type libvlc_video_output_resize_cb = Option<unsafe extern "C" fn(*mut c_void, c_uint, c_uint)>;
 
@@ -921,6 +1070,12 @@ extern "C" {
         p_mi: *mut libvlc_media_player_t,
         psz_subtitle: *const c_char,
     ) -> c_int;
+    pub fn libvlc_media_player_add_slave(
+        p_mi: *mut libvlc_media_player_t,
+        i_type: libvlc_media_slave_type_t,
+        psz_uri: *const c_char,
+        b_select: bool,
+    ) -> c_int;
     pub fn libvlc_video_get_spu_delay(p_mi: *mut libvlc_media_player_t) -> i64;
     pub fn libvlc_video_set_spu_delay(p_mi: *mut libvlc_media_player_t, i_delay: i64) -> c_int;
     pub fn libvlc_video_get_title_description(
@@ -1029,6 +1184,7 @@ extern "C" {
         module: *const c_char,
         device_id: *const c_char,
     );
+    pub fn libvlc_audio_output_device_get(mp: *mut libvlc_media_player_t) -> *mut c_char;
     pub fn libvlc_audio_toggle_mute(p_mi: *mut libvlc_media_player_t);
     pub fn libvlc_audio_get_mute(p_mi: *mut libvlc_media_player_t) -> c_int;
     pub fn libvlc_audio_set_mute(p_mi: *mut libvlc_media_player_t, status: c_int);
@@ -1116,7 +1272,8 @@ pub mod libvlc_event_t_types {
     #[repr(C)]
     #[derive(Clone, Copy)]
     pub struct media_meta_changed {
-        pub meta_type: libvlc_meta_t,
+        /// A `libvlc_meta_t`, kept as an int as newer libvlc may send values `Meta` lacks.
+        pub meta_type: c_int,
     }
     #[repr(C)]
     #[derive(Clone, Copy)]
@@ -1141,7 +1298,8 @@ pub mod libvlc_event_t_types {
     #[repr(C)]
     #[derive(Clone, Copy)]
     pub struct media_state_changed {
-        pub new_state: libvlc_state_t,
+        /// A `libvlc_state_t`.
+        pub new_state: c_int,
     }
     #[repr(C)]
     #[derive(Clone, Copy)]
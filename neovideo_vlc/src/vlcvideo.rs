@@ -1,20 +1,32 @@
 // use super::{support::gl, support::Gl};
+use std::ffi::CStr;
 use std::sync::{Arc, Mutex};
-use std::{ffi::CStr, panic};
 
 mod gl {
     #![allow(clippy::all)]
     include!(concat!(env!("OUT_DIR"), "/gl_bindings.rs"));
 }
 
+use super::audio_output::{AudioDevice, AudioOutput};
+use super::audio_sink::{AudioSink, SinkSlot};
+use super::color::{ColorInfo, Primaries, ToneMapping, Transfer, HDR_PEAK, SDR_WHITE};
+use super::equalizer::Equalizer;
+use super::error::{NeovideoError, Result};
+use super::instance::Instance;
+use super::layout::{AspectRatio, ScaleMode, VideoQuad};
+use super::media::Media;
+use super::media_player::MediaPlayer;
+use super::playlist_file::mrl_to_path;
+use super::sidecar::find_sidecar_subtitles;
+use super::source::FrameNotifier;
+use super::swvideo::{Chroma, CpuFrame, SoftwareOutput};
+use super::tools::{ffi_guard, lock};
+use super::track::{Track, TrackDescription};
+use super::triple_buffer::{triple_buffer, Reader, Writer};
 use super::vlc::{
-    libvlc_instance_t, libvlc_media_new_location, libvlc_media_player_new_from_media,
-    libvlc_media_player_play, libvlc_media_player_release, libvlc_media_player_t,
-    libvlc_media_release, libvlc_media_t, libvlc_new, libvlc_release,
-    libvlc_video_color_primaries_t, libvlc_video_color_space_t, libvlc_video_engine_t,
-    libvlc_video_orient_t, libvlc_video_output_cfg_t, libvlc_video_render_cfg_t,
-    libvlc_video_set_output_callbacks, libvlc_video_setup_device_cfg_t,
-    libvlc_video_setup_device_info_t, libvlc_video_transfer_func_t,
+    libvlc_video_engine_t, libvlc_video_orient_t, libvlc_video_output_cfg_t,
+    libvlc_video_render_cfg_t, libvlc_video_set_output_callbacks, libvlc_video_setup_device_cfg_t,
+    libvlc_video_setup_device_info_t, AudioChannel, State, TrackType,
 };
 use glutin::event_loop::EventLoopWindowTarget;
 use glutin::{dpi::PhysicalSize, Context, ContextBuilder, GlProfile, NotCurrent, PossiblyCurrent};
@@ -38,138 +50,307 @@ void main()
 }
 \0";
 
+/// Samples the video, decoding HDR and wide gamut pictures to linear light, mapping
+/// them into BT.709 and tone mapping them for an sRGB display. The math follows
+/// `Transfer::to_linear` and `ToneMapping::apply`.
 const FS_SRC: &'static [u8] = b"
 #version 410
 uniform sampler2D u_videotex;
+// 0 samples the picture as it is, 1 decodes PQ, 2 HLG, 3 gamma 2.4 and 4 linear light
+uniform int u_transfer;
+// linear source RGB to linear BT.709
+uniform mat3 u_gamut;
+// 0 Reinhard, 1 Hable, 2 BT.2390
+uniform int u_tone_mapping;
+// brightest light of the source, relative to SDR reference white
+uniform float u_peak;
+// SDR reference white in nits
+uniform float u_sdr_white;
 in vec2 v_TexCoordinate;
 out vec4 outColor;
+
+const float PQ_M1 = 0.1593017578125;
+const float PQ_M2 = 78.84375;
+const float PQ_C1 = 0.8359375;
+const float PQ_C2 = 18.8515625;
+const float PQ_C3 = 18.6875;
+
+vec3 pq_to_linear(vec3 e)
+{
+ vec3 p = pow(clamp(e, 0.0, 1.0), vec3(1.0 / PQ_M2));
+ vec3 nits = pow(max(p - PQ_C1, 0.0) / (PQ_C2 - PQ_C3 * p), vec3(1.0 / PQ_M1)) * 10000.0;
+ return nits / u_sdr_white;
+}
+
+float linear_to_pq(float l)
+{
+ float y = pow(max(l * u_sdr_white / 10000.0, 0.0), PQ_M1);
+ return pow((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y), PQ_M2);
+}
+
+vec3 hlg_to_linear(vec3 e)
+{
+ const float a = 0.17883277;
+ const float b = 0.28466892;
+ const float c = 0.55991073;
+ vec3 low = e * e / 3.0;
+ vec3 high = (exp((e - c) / a) + b) / 12.0;
+ vec3 scene = mix(low, high, step(0.5, e));
+ float luma = dot(scene, vec3(0.2627, 0.6780, 0.0593));
+ // the reference OOTF for a display of the source peak
+ return scene * pow(max(luma, 1e-6), 0.2) * u_peak;
+}
+
+vec3 reinhard(vec3 x)
+{
+ // extended so that the peak lands on white
+ return x * (1.0 + x / (u_peak * u_peak)) / (1.0 + x);
+}
+
+vec3 hable_curve(vec3 x)
+{
+ const float A = 0.15;
+ const float B = 0.50;
+ const float C = 0.10;
+ const float D = 0.20;
+ const float E = 0.02;
+ const float F = 0.30;
+ return (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F;
+}
+
+vec3 hable(vec3 x)
+{
+ return hable_curve(x) / hable_curve(vec3(u_peak));
+}
+
+vec3 bt2390(vec3 x)
+{
+ // the EETF on the brightest channel, in PQ space normalized to the source peak
+ float m = max(max(x.r, x.g), x.b);
+ if (m <= 0.0) {
+  return x;
+ }
+ float source_peak = linear_to_pq(u_peak);
+ float e1 = linear_to_pq(m) / source_peak;
+ float max_lum = linear_to_pq(1.0) / source_peak;
+ float ks = 1.5 * max_lum - 0.5;
+ float e2 = e1;
+ if (e1 > ks) {
+  float t = (e1 - ks) / (1.0 - ks);
+  float t2 = t * t;
+  float t3 = t2 * t;
+  e2 = (2.0 * t3 - 3.0 * t2 + 1.0) * ks + (t3 - 2.0 * t2 + t) * (1.0 - ks)
+   + (-2.0 * t3 + 3.0 * t2) * max_lum;
+ }
+ return x * (pq_to_linear(vec3(e2 * source_peak)).r / m);
+}
+
+vec3 linear_to_srgb(vec3 l)
+{
+ l = clamp(l, 0.0, 1.0);
+ return mix(l * 12.92, 1.055 * pow(l, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, l));
+}
+
 void main()
 {
- outColor = texture(u_videotex, v_TexCoordinate);
+ vec4 color = texture(u_videotex, v_TexCoordinate);
+ if (u_transfer == 0) {
+  outColor = color;
+  return;
+ }
+ vec3 rgb = color.rgb;
+ if (u_transfer == 1) {
+  rgb = pq_to_linear(rgb);
+ } else if (u_transfer == 2) {
+  rgb = hlg_to_linear(rgb);
+ } else if (u_transfer == 3) {
+  rgb = pow(max(rgb, 0.0), vec3(2.4));
+ }
+ rgb = max(u_gamut * rgb, 0.0);
+ if (u_peak > 1.0) {
+  if (u_tone_mapping == 0) {
+   rgb = reinhard(rgb);
+  } else if (u_tone_mapping == 1) {
+   rgb = hable(rgb);
+  } else {
+   rgb = bt2390(rgb);
+  }
+ }
+ outColor = vec4(linear_to_srgb(rgb), color.a);
 }
 \0";
 
 #[rustfmt::skip]
 static RECT_DATA: [f32; 16] = [
-	0f32, 0.0f32, 0f32, 1.0f32,
-	0f32, -0.95f32, 0f32, 0f32,
-	1f32, 0f32, 1f32, 1f32,
-	1f32, -0.95f32, 1f32, 0f32,
+	-1f32, 1f32, 0f32, 1f32,
+	-1f32, -1f32, 0f32, 0f32,
+	1f32, 1f32, 1f32, 1f32,
+	1f32, -1f32, 1f32, 0f32,
 ];
 
 pub struct TextureRender {
     vao: u32,
+    vbo: u32,
     program: u32,
     tex_uniform: i32,
+    transfer_uniform: i32,
+    gamut_uniform: i32,
+    tone_mapping_uniform: i32,
+    peak_uniform: i32,
+    sdr_white_uniform: i32,
+    scale_mode: ScaleMode,
+    aspect_ratio: AspectRatio,
+    color_info: ColorInfo,
+    tone_mapping: ToneMapping,
 }
 
-macro_rules! glchk {
-	($($s:stmt;)*) => {
-		$(
-			$s
-			if cfg!(debug_assertions) {
-				let err = gl::GetError();
-				if err != gl::NO_ERROR {
-					let err_str = match err {
-						gl::INVALID_ENUM => "GL_INVALID_ENUM",
-						gl::INVALID_VALUE => "GL_INVALID_VALUE",
-						gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
-						gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
-						gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
-						_ => "unknown error"
-					};
-					println!("{}:{} - {} caused {}",
-							 file!(),
-							 line!(),
-							 stringify!($s),
-							 err_str);
-				}
-			}
-		)*
-	};
+/// Compiles a shader of `kind` from the nul terminated `src`.
+unsafe fn compile_shader(kind: u32, src: &[u8]) -> Result<u32> {
+    let shader = gl::CreateShader(kind);
+    gl::ShaderSource(
+        shader,
+        1,
+        [src.as_ptr() as *const _].as_ptr(),
+        std::ptr::null(),
+    );
+    gl::CompileShader(shader);
+    let mut status = gl::FALSE as i32;
+    gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
+    if status == gl::TRUE as i32 {
+        return Ok(shader);
+    }
+    let mut len = 0;
+    gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
+    let mut log = vec![0u8; len.max(1) as usize];
+    gl::GetShaderInfoLog(shader, len, &mut len, log.as_mut_ptr() as *mut _);
+    gl::DeleteShader(shader);
+    log.truncate(len.max(0) as usize);
+    Err(NeovideoError::GlSetup(format!(
+        "compiling a shader failed: {}",
+        String::from_utf8_lossy(&log)
+    )))
 }
 
-fn print_shader_info(shader: u32) {
-    unsafe {
-        let mut len: i32 = std::mem::zeroed();
-        gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-        if len < 1 {
-            return;
-        }
-        let mut count: i32 = std::mem::zeroed();
-        let mut infos = vec![0u8; len as usize + 100];
-        gl::GetShaderInfoLog(shader, len, &mut count, infos.as_mut_ptr() as *mut i8);
-        println!("Shader Info: {:?}", String::from_utf8(infos));
-        let mut status: i32 = gl::TRUE as i32;
-        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut status);
-        if status == gl::FALSE as i32 {
-            println!("compile shader failed");
-        }
+/// Links a program of the shaders, which are deleted either way.
+unsafe fn link_program(vs: u32, fs: u32) -> Result<u32> {
+    let program = gl::CreateProgram();
+    gl::AttachShader(program, vs);
+    gl::AttachShader(program, fs);
+    gl::LinkProgram(program);
+    gl::DeleteShader(vs);
+    gl::DeleteShader(fs);
+    let mut status = gl::FALSE as i32;
+    gl::GetProgramiv(program, gl::LINK_STATUS, &mut status);
+    if status == gl::TRUE as i32 {
+        return Ok(program);
     }
+    let mut len = 0;
+    gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+    let mut log = vec![0u8; len.max(1) as usize];
+    gl::GetProgramInfoLog(program, len, &mut len, log.as_mut_ptr() as *mut _);
+    gl::DeleteProgram(program);
+    log.truncate(len.max(0) as usize);
+    Err(NeovideoError::GlSetup(format!(
+        "linking a shader program failed: {}",
+        String::from_utf8_lossy(&log)
+    )))
 }
 
 impl TextureRender {
+    /// Draws `tex`, holding a picture of `video_size` pixels, into a viewport of
+    /// `viewport` pixels.
     #[inline]
-    pub fn draw_video_frame(&self, tex: u32) {
+    pub fn draw_video_frame(&self, tex: u32, video_size: (u32, u32), viewport: (u32, u32)) {
+        let quad = match VideoQuad::new(
+            video_size,
+            (viewport.0 as f32, viewport.1 as f32),
+            self.scale_mode,
+            self.aspect_ratio,
+        ) {
+            Some(quad) => quad,
+            None => return,
+        };
+        // to normalized device coordinates, the texture is stored bottom-up
+        let [left, top, right, bottom] = quad.rect;
+        let [u0, v0, u1, v1] = quad.uv;
+        let x0 = left / viewport.0 as f32 * 2.0 - 1.0;
+        let x1 = right / viewport.0 as f32 * 2.0 - 1.0;
+        let y0 = 1.0 - top / viewport.1 as f32 * 2.0;
+        let y1 = 1.0 - bottom / viewport.1 as f32 * 2.0;
+        #[rustfmt::skip]
+        let rect_data: [f32; 16] = [
+            x0, y0, u0, 1.0 - v0,
+            x0, y1, u0, 1.0 - v1,
+            x1, y0, u1, 1.0 - v0,
+            x1, y1, u1, 1.0 - v1,
+        ];
+        let color = self.color_info;
+        let transfer = match color.transfer {
+            _ if !color.needs_conversion() => 0,
+            Transfer::Pq => 1,
+            Transfer::Hlg => 2,
+            Transfer::Linear => 4,
+            _ => 3,
+        };
+        let gamut = color.primaries.conversion_to(Primaries::Bt709);
+        let tone_mapping = match self.tone_mapping {
+            ToneMapping::Reinhard => 0,
+            ToneMapping::Hable => 1,
+            ToneMapping::Bt2390 => 2,
+        };
+        let peak = if color.is_hdr() {
+            HDR_PEAK / SDR_WHITE
+        } else {
+            1.0
+        };
         unsafe {
-            println!(
-                "draw tex: {}, vao: {}, program: {}",
-                tex, self.vao, self.program
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                0,
+                std::mem::size_of_val(&rect_data) as gl::types::GLsizeiptr,
+                rect_data.as_ptr() as *const _,
             );
-            glchk!(
-                gl::Disable(gl::BLEND);
-                gl::BindVertexArray(self.vao);
-                gl::UseProgram(self.program);
-                gl::ActiveTexture(gl::TEXTURE4);
-                gl::Uniform1i(self.tex_uniform, 4);
-                gl::BindTexture(gl::TEXTURE_2D, tex);
-                gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
-                gl::BindTexture(gl::TEXTURE_2D, 0);
-                gl::Enable(gl::BLEND);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::Disable(gl::BLEND);
+            gl::BindVertexArray(self.vao);
+            gl::UseProgram(self.program);
+            gl::ActiveTexture(gl::TEXTURE4);
+            gl::Uniform1i(self.tex_uniform, 4);
+            gl::Uniform1i(self.transfer_uniform, transfer);
+            gl::UniformMatrix3fv(
+                self.gamut_uniform,
+                1,
+                gl::TRUE,
+                gamut.as_ptr() as *const f32,
             );
+            gl::Uniform1i(self.tone_mapping_uniform, tone_mapping);
+            gl::Uniform1f(self.peak_uniform, peak);
+            gl::Uniform1f(self.sdr_white_uniform, SDR_WHITE);
+            gl::BindTexture(gl::TEXTURE_2D, tex);
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::Enable(gl::BLEND);
         }
     }
-    pub fn new(window_context: &Context<PossiblyCurrent>) -> TextureRender {
+
+    /// Sets up the shaders and buffers in `window_context`, which must be current.
+    pub fn new(window_context: &Context<PossiblyCurrent>) -> Result<TextureRender> {
         unsafe {
             gl::load_with(|sym| window_context.get_proc_address(sym) as *const _);
-            let mut max_attribs = 0;
-            gl::GetIntegerv(gl::MAX_VERTEX_ATTRIBS, &mut max_attribs);
-            println!("MAX_VERTEX_ATTRIBS: {}", max_attribs);
-            let program = {
-                let vs = gl::CreateShader(gl::VERTEX_SHADER);
-                gl::ShaderSource(
-                    vs,
-                    1,
-                    [VS_SRC.as_ptr() as *const _].as_ptr(),
-                    std::ptr::null(),
-                );
-                gl::CompileShader(vs);
-
-                let fs = gl::CreateShader(gl::FRAGMENT_SHADER);
-                gl::ShaderSource(
-                    fs,
-                    1,
-                    [FS_SRC.as_ptr() as *const _].as_ptr(),
-                    std::ptr::null(),
-                );
-                gl::CompileShader(fs);
-
-                print_shader_info(vs);
-                print_shader_info(fs);
-
-                let program = gl::CreateProgram();
-                gl::AttachShader(program, vs);
-                gl::AttachShader(program, fs);
-                gl::LinkProgram(program);
-                gl::DeleteShader(vs);
-                gl::DeleteShader(fs);
-                program
+            let vs = compile_shader(gl::VERTEX_SHADER, VS_SRC)?;
+            let fs = match compile_shader(gl::FRAGMENT_SHADER, FS_SRC) {
+                Ok(fs) => fs,
+                Err(e) => {
+                    gl::DeleteShader(vs);
+                    return Err(e);
+                }
             };
+            let program = link_program(vs, fs)?;
 
             let mut vao: u32 = std::mem::zeroed();
-            glchk!(
-                gl::GenVertexArrays(1, &mut vao);
-                gl::BindVertexArray(vao);
+            gl::GenVertexArrays(1, &mut vao);
+            gl::BindVertexArray(vao);
 
             let mut vbo: u32 = std::mem::zeroed();
             gl::GenBuffers(1, &mut vbo);
@@ -178,7 +359,7 @@ impl TextureRender {
                 gl::ARRAY_BUFFER,
                 (std::mem::size_of::<f32>() * RECT_DATA.len()) as gl::types::GLsizeiptr,
                 RECT_DATA.as_ptr() as *const _,
-                gl::STATIC_DRAW,
+                gl::DYNAMIC_DRAW,
             );
 
             let pos_attrib =
@@ -208,41 +389,484 @@ impl TextureRender {
             let tex_uniform = gl::GetUniformLocation(program, b"u_videotex\0".as_ptr() as *const _);
             gl::ActiveTexture(gl::TEXTURE4);
             gl::Uniform1i(tex_uniform, 4);
-
-            );
-
-            println!("tex_uniform: {}", tex_uniform);
-            TextureRender {
+            let uniform = |name: &[u8]| gl::GetUniformLocation(program, name.as_ptr() as *const _);
+            Ok(TextureRender {
                 vao,
+                vbo,
                 program,
                 tex_uniform,
-            }
+                transfer_uniform: uniform(b"u_transfer\0"),
+                gamut_uniform: uniform(b"u_gamut\0"),
+                tone_mapping_uniform: uniform(b"u_tone_mapping\0"),
+                peak_uniform: uniform(b"u_peak\0"),
+                sdr_white_uniform: uniform(b"u_sdr_white\0"),
+                scale_mode: ScaleMode::default(),
+                aspect_ratio: AspectRatio::default(),
+                color_info: ColorInfo::default(),
+                tone_mapping: ToneMapping::default(),
+            })
+        }
+    }
+
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    pub fn set_scale_mode(&mut self, mode: ScaleMode) {
+        self.scale_mode = mode;
+    }
+
+    pub fn aspect_ratio(&self) -> AspectRatio {
+        self.aspect_ratio
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: AspectRatio) {
+        self.aspect_ratio = aspect_ratio;
+    }
+
+    pub fn color_info(&self) -> ColorInfo {
+        self.color_info
+    }
+
+    /// Describes the textures drawn from now on, HDR and wide gamut pictures are
+    /// converted for an sRGB display.
+    pub fn set_color_info(&mut self, color_info: ColorInfo) {
+        self.color_info = color_info;
+    }
+
+    pub fn tone_mapping(&self) -> ToneMapping {
+        self.tone_mapping
+    }
+
+    /// The operator bringing HDR pictures into SDR range.
+    pub fn set_tone_mapping(&mut self, tone_mapping: ToneMapping) {
+        self.tone_mapping = tone_mapping;
+    }
+}
+
+/// The latest decoded frame, in the form the active output produces.
+#[derive(Clone, Copy, Debug)]
+pub enum VideoFrame<'a> {
+    /// A texture in the GL context passed to `VLCVideo::new`, stored bottom-up.
+    Texture(u32),
+    /// A picture in CPU memory, stored top-down.
+    Cpu(&'a CpuFrame),
+}
+
+/// A texture libvlc renders a frame into, with the fences that hand it over between
+/// the libvlc context and the window's context.
+#[derive(Default)]
+struct RenderTarget {
+    texture: u32,
+    /// Only valid in the libvlc context, framebuffers are not shared.
+    fbo: u32,
+    width: u32,
+    height: u32,
+    /// Internal format of the texture.
+    format: u32,
+    /// Colour of the frame, handed over with it like its size.
+    color_info: ColorInfo,
+    /// Signalled once libvlc finished drawing the frame.
+    rendered: Option<gl::types::GLsync>,
+    /// Signalled once the window finished sampling the frame.
+    sampled: Option<gl::types::GLsync>,
+}
+
+// textures and sync objects belong to the share group, not to a thread
+unsafe impl Send for RenderTarget {}
+
+impl RenderTarget {
+    /// Sizes the texture for `width` by `height` pixels of `format`, its name stays the
+    /// same. Returns `false` if the framebuffer cannot be drawn into.
+    unsafe fn allocate(&mut self, width: u32, height: u32, format: u32) -> bool {
+        if self.texture == 0 {
+            gl::GenTextures(1, &mut self.texture);
+            gl::GenFramebuffers(1, &mut self.fbo);
+        }
+        if (self.width, self.height, self.format) == (width, height, format) {
+            return true;
+        }
+        let pixel_type = match format {
+            gl::RGBA16F => gl::HALF_FLOAT,
+            gl::RGB10_A2 => gl::UNSIGNED_INT_2_10_10_10_REV,
+            _ => gl::UNSIGNED_BYTE,
+        };
+        gl::BindTexture(gl::TEXTURE_2D, self.texture);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            format as i32,
+            width as i32,
+            height as i32,
+            0,
+            gl::RGBA,
+            pixel_type,
+            std::ptr::null_mut(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            self.texture,
+            0,
+        );
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+            // sized again on the next try
+            self.width = 0;
+            self.height = 0;
+            return false;
+        }
+        self.width = width;
+        self.height = height;
+        self.format = format;
+        true
+    }
+
+    /// Makes the current context wait for `fence` on the GPU, without blocking here.
+    unsafe fn wait(fence: &mut Option<gl::types::GLsync>) {
+        if let Some(fence) = fence.take() {
+            gl::WaitSync(fence, 0, gl::TIMEOUT_IGNORED);
+            gl::DeleteSync(fence);
+        }
+    }
+
+    /// Sets `fence` after the commands issued so far, replacing an unwaited one.
+    unsafe fn signal(fence: &mut Option<gl::types::GLsync>) {
+        if let Some(old) = fence.take() {
+            gl::DeleteSync(old);
         }
+        *fence = Some(gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0));
+        // other contexts only see the fence once it reached the GPU
+        gl::Flush();
+    }
+
+    /// Frees everything, the libvlc context must be current.
+    unsafe fn delete(&mut self) {
+        for fence in [self.rendered.take(), self.sampled.take()]
+            .into_iter()
+            .flatten()
+        {
+            gl::DeleteSync(fence);
+        }
+        if self.texture != 0 {
+            gl::DeleteTextures(1, &self.texture);
+            gl::DeleteFramebuffers(1, &self.fbo);
+        }
+        *self = RenderTarget::default();
     }
 }
 
-pub struct VLCVideo {
-    vlc: *mut libvlc_instance_t,
-    player: *mut libvlc_media_player_t,
-    media: *mut libvlc_media_t,
+/// The texture format holding a frame of `color_info` without banding, HDR is kept
+/// above 1.0 for the tone mapping.
+fn texture_format(color_info: &ColorInfo) -> u32 {
+    if color_info.is_hdr() {
+        gl::RGBA16F
+    } else if color_info.bit_depth > 8 {
+        gl::RGB10_A2
+    } else {
+        gl::RGBA8
+    }
+}
+
+/// The OpenGL output. libvlc draws on its video thread through the `Renderer` and the
+/// frames reach the window's thread through the triple buffer, so the window never
+/// waits on a lock libvlc holds.
+struct GlOutput {
+    /// Shown by `get_video_frame` on the window's thread.
+    reader: Reader<RenderTarget>,
+    renderer: Arc<Renderer>,
+}
+
+/// The side of the OpenGL output used by the libvlc callbacks. libvlc is handed the
+/// address of the `Arc` contents, which stays put however the `VLCVideo` moves, and the
+/// `VLCVideo` stops its player before letting go of it.
+struct Renderer {
+    /// Locked by the libvlc callbacks, and by `delete_render_targets` once the player
+    /// is stopped.
+    state: Mutex<GlState>,
+    /// Called from `swap` once a new frame is ready.
+    frame_notifier: Mutex<Option<FrameNotifier>>,
+}
+
+struct GlState {
+    /// `None` once switching it failed, the output is dead then.
     shared_context: Option<SharedContext>,
-    textures: [u32; 3],
-    fbo: [u32; 3],
-    idx_render: usize,
-    idx_swap: usize,
-    idx_display: usize,
-    update: Arc<Mutex<bool>>,
+    /// Drawn into by libvlc.
+    writer: Writer<RenderTarget>,
+    /// Size and colour of the stream being decoded, the render targets are allocated
+    /// for them.
     width: u32,
     height: u32,
+    color_info: ColorInfo,
 }
 
-impl Drop for VLCVideo {
-    fn drop(&mut self) {
-        self.stop();
+// the context is current on a thread only between the two `make_current` calls of a
+// frame, or within `delete_render_targets`, and never moves while current
+unsafe impl Send for GlState {}
+
+impl GlState {
+    /// Moves the libvlc context in or out of the calling thread, returns `false` if
+    /// that failed.
+    fn make_current(&mut self, current: bool) -> bool {
+        let (context, done) = match (self.shared_context.take(), current) {
+            (Some(SharedContext::NotCurrent(context)), true) => {
+                match unsafe { context.make_current() } {
+                    Ok(context) => (Some(SharedContext::Current(context)), true),
+                    Err((context, _)) => (Some(SharedContext::NotCurrent(context)), false),
+                }
+            }
+            (Some(SharedContext::Current(context)), false) => {
+                match unsafe { context.make_not_current() } {
+                    Ok(context) => (Some(SharedContext::NotCurrent(context)), true),
+                    Err(_) => (None, false),
+                }
+            }
+            // already where libvlc wants it
+            (context, _) => {
+                let done = context.is_some();
+                (context, done)
+            }
+        };
+        self.shared_context = context;
+        done
+    }
+
+    /// Prepares the target libvlc draws the next frame into and binds it, in the libvlc
+    /// context.
+    unsafe fn bind_render_target(&mut self) -> bool {
+        let (width, height) = (self.width, self.height);
+        let format = texture_format(&self.color_info);
+        let target = self.writer.buffer();
+        // a frame replaced before the window took it
+        if let Some(fence) = target.rendered.take() {
+            gl::DeleteSync(fence);
+        }
+        RenderTarget::wait(&mut target.sampled);
+        if !target.allocate(width, height, format) {
+            return false;
+        }
+        target.color_info = self.color_info;
+        gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+        true
+    }
+}
+
+impl GlOutput {
+    fn new(shared_context: Context<NotCurrent>) -> GlOutput {
+        let (writer, reader) = triple_buffer(Default::default());
+        GlOutput {
+            reader,
+            renderer: Arc::new(Renderer {
+                state: Mutex::new(GlState {
+                    shared_context: Some(SharedContext::NotCurrent(shared_context)),
+                    writer,
+                    width: 0,
+                    height: 0,
+                    color_info: ColorInfo::default(),
+                }),
+                frame_notifier: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Routes the video of `player` into this output, the player keeps the renderer
+    /// alive. Must be called before the player starts.
+    fn attach(&self, player: &MediaPlayer) {
+        player.keep_alive(self.renderer.clone());
         unsafe {
-            if self.vlc as *mut _ != std::ptr::null_mut() {
-                libvlc_release(self.vlc);
+            libvlc_video_set_output_callbacks(
+                player.raw(),
+                libvlc_video_engine_t::libvlc_video_engine_opengl,
+                Some(Renderer::setup),
+                // the render targets are reused by the next media
+                None,
+                None,
+                Some(Renderer::resize),
+                Some(Renderer::swap),
+                Some(Renderer::make_current),
+                Some(Renderer::get_proc_address),
+                None,
+                None,
+                Arc::as_ptr(&self.renderer) as *mut c_void,
+            );
+        }
+    }
+
+    fn set_frame_notifier(&self, notifier: Option<FrameNotifier>) {
+        *lock(&self.renderer.frame_notifier) = notifier;
+    }
+
+    /// The frame held for the window, `None` before the first one.
+    fn frame(&self) -> Option<&RenderTarget> {
+        let target = self.reader.buffer();
+        if target.texture == 0 || target.width == 0 || target.height == 0 {
+            None
+        } else {
+            Some(target)
+        }
+    }
+
+    fn video_size(&self) -> Option<(u32, u32)> {
+        self.frame().map(|target| (target.width, target.height))
+    }
+
+    fn color_info(&self) -> Option<ColorInfo> {
+        self.frame().map(|target| target.color_info)
+    }
+
+    /// The texture of the latest frame, `update` tells whether it changed since the
+    /// last call. The window's context must be current.
+    fn texture(&mut self, update: &mut bool) -> u32 {
+        let reader = &mut self.reader;
+        *update = reader.has_update();
+        if *update {
+            unsafe {
+                // the window may still be drawing the frame handed back to libvlc
+                let held = reader.buffer_mut();
+                if held.texture != 0 {
+                    RenderTarget::signal(&mut held.sampled);
+                }
+                reader.update();
+                RenderTarget::wait(&mut reader.buffer_mut().rendered);
+            }
+        }
+        reader.buffer().texture
+    }
+
+    /// Frees the GL objects of the render targets in the libvlc context, the player
+    /// must be stopped.
+    fn delete_render_targets(&mut self) {
+        let mut state = lock(&self.renderer.state);
+        if !state.make_current(true) {
+            return;
+        }
+        for target in state.writer.buffers_mut(&mut self.reader) {
+            unsafe { target.delete() };
+        }
+        state.make_current(false);
+    }
+}
+
+impl Renderer {
+    unsafe extern "C" fn setup(
+        data: *mut *mut c_void,
+        _cfg: *const libvlc_video_setup_device_cfg_t,
+        _out: *mut libvlc_video_setup_device_info_t,
+    ) -> bool {
+        ffi_guard(false, || {
+            let that = &*(*data as *const Renderer);
+            let mut state = lock(&that.state);
+            state.width = 0;
+            state.height = 0;
+            state.shared_context.is_some()
+        })
+    }
+
+    unsafe extern "C" fn swap(data: *mut c_void) {
+        ffi_guard((), || {
+            let that = &*(data as *const Renderer);
+            {
+                let mut state = lock(&that.state);
+                RenderTarget::signal(&mut state.writer.buffer().rendered);
+                state.writer.publish();
+                // libvlc goes on drawing into the old target if this fails
+                state.bind_render_target();
+            }
+            if let Some(notify) = &*lock(&that.frame_notifier) {
+                notify();
+            }
+        })
+    }
+
+    unsafe extern "C" fn make_current(data: *mut c_void, current: bool) -> bool {
+        ffi_guard(false, || {
+            let that = &*(data as *const Renderer);
+            lock(&that.state).make_current(current)
+        })
+    }
+
+    unsafe extern "C" fn get_proc_address(data: *mut c_void, name: *const i8) -> *mut c_void {
+        ffi_guard(std::ptr::null_mut(), || {
+            let that = &*(data as *const Renderer);
+            let name = match CStr::from_ptr(name).to_str() {
+                Ok(name) => name,
+                Err(_) => return std::ptr::null_mut(),
+            };
+            match &*lock(&that.state) {
+                GlState {
+                    shared_context: Some(SharedContext::Current(context)),
+                    ..
+                } => context.get_proc_address(name) as *mut c_void,
+                _ => std::ptr::null_mut(),
+            }
+        })
+    }
+
+    unsafe extern "C" fn resize(
+        data: *mut c_void,
+        cfg: *const libvlc_video_render_cfg_t,
+        render_cfg: *mut libvlc_video_output_cfg_t,
+    ) -> bool {
+        ffi_guard(false, || {
+            let that = &*(data as *const Renderer);
+            let color_info = ColorInfo::from_render_cfg(cfg);
+            let cfg = &*(cfg);
+            let render_cfg = &mut *render_cfg;
+            let mut state = lock(&that.state);
+            state.width = cfg.width;
+            state.height = cfg.height;
+            state.color_info = color_info;
+            // the other targets are resized once they come back from the window
+            if !state.bind_render_target() {
+                return false;
             }
+            render_cfg.u.opengl_format = gl::RGBA as i32;
+            // libvlc converts YCbCr to full range RGB but leaves primaries and transfer to
+            // `TextureRender`, which needs them as the stream has them
+            render_cfg.full_range = true;
+            render_cfg.colorspace = color_info.space.to_raw();
+            render_cfg.primaries = color_info.primaries.to_raw();
+            render_cfg.transfer = color_info.transfer.to_raw();
+            render_cfg.orientation = libvlc_video_orient_t::libvlc_video_orient_top_left;
+            true
+        })
+    }
+}
+
+pub struct VLCVideo {
+    vlc: Instance,
+    player: Option<MediaPlayer>,
+    media: Option<Media>,
+    /// Set for the OpenGL output.
+    gl_output: Option<GlOutput>,
+    /// Set for the software output, frames are then decoded into CPU memory.
+    software: Option<Box<SoftwareOutput>>,
+    /// Whether subtitle files named after a local media are loaded with it.
+    sidecar_subtitles: bool,
+    /// Why sidecar subtitles of the current media could not be loaded.
+    sidecar_errors: Vec<NeovideoError>,
+    /// Kept to apply to the player of every new media.
+    equalizer: Option<Equalizer>,
+    /// Audio output module and device chosen for new players, `None` for the defaults.
+    audio_output: Option<String>,
+    audio_device: Option<String>,
+    /// Sink for the audio of new players, `None` plays through the audio output.
+    audio_sink: Option<Arc<SinkSlot>>,
+    /// The sink the player writes to, released only along with the player.
+    player_audio_sink: Option<Arc<SinkSlot>>,
+}
+
+impl Drop for VLCVideo {
+    fn drop(&mut self) {
+        self.release_player();
+        if let Some(output) = &mut self.gl_output {
+            output.delete_render_targets();
         }
     }
 }
@@ -251,218 +875,532 @@ impl VLCVideo {
     pub fn new<T>(
         window_context: &Context<PossiblyCurrent>,
         el: &EventLoopWindowTarget<T>,
-    ) -> VLCVideo {
+    ) -> Result<VLCVideo> {
+        VLCVideo::with_args::<T, &str>(window_context, el, &[])
+    }
+
+    /// Like `new`, passing `args` such as `--no-audio` on to `libvlc_new`.
+    pub fn with_args<T, S: AsRef<str>>(
+        window_context: &Context<PossiblyCurrent>,
+        el: &EventLoopWindowTarget<T>,
+        args: &[S],
+    ) -> Result<VLCVideo> {
+        let vlc = Instance::with_args(args)?;
         unsafe {
-            let vlc = libvlc_new(0, std::ptr::null_mut());
             let shared_context = ContextBuilder::new()
                 .with_gl_profile(GlProfile::Compatibility)
                 .with_shared_lists(window_context)
                 .build_headless(el, PhysicalSize::new(1920, 1080))
-                .unwrap();
+                .map_err(|e| NeovideoError::GlSetup(e.to_string()))?;
 
-            let current_context = shared_context.make_current().unwrap();
+            let current_context = shared_context
+                .make_current()
+                .map_err(|(_, e)| NeovideoError::GlSetup(e.to_string()))?;
 
             gl::load_with(|sym| current_context.get_proc_address(sym) as *const _);
 
-            let shared_context = current_context.make_not_current().unwrap();
-            VLCVideo {
+            let shared_context = current_context
+                .make_not_current()
+                .map_err(|(_, e)| NeovideoError::GlSetup(e.to_string()))?;
+            Ok(VLCVideo {
                 vlc,
-                player: std::ptr::null_mut(),
-                media: std::ptr::null_mut(),
-                shared_context: Some(SharedContext::NotCurrent(shared_context)),
-                textures: [0; 3],
-                fbo: [0; 3],
-                idx_render: 0usize,
-                idx_swap: 1usize,
-                idx_display: 2usize,
-                update: Arc::new(Mutex::new(false)),
-                width: 0u32,
-                height: 0u32,
-            }
+                player: None,
+                media: None,
+                gl_output: Some(GlOutput::new(shared_context)),
+                software: None,
+                sidecar_subtitles: true,
+                sidecar_errors: Vec::new(),
+                equalizer: None,
+                audio_output: None,
+                audio_device: None,
+                audio_sink: None,
+                player_audio_sink: None,
+            })
         }
     }
 
+    /// Creates a player decoding into CPU memory instead of GL textures, it needs
+    /// neither a window nor a GL context.
+    pub fn new_software<S: AsRef<str>>(chroma: Chroma, args: &[S]) -> Result<VLCVideo> {
+        Ok(VLCVideo {
+            vlc: Instance::with_args(args)?,
+            player: None,
+            media: None,
+            gl_output: None,
+            software: Some(Box::new(SoftwareOutput::new(chroma))),
+            sidecar_subtitles: true,
+            sidecar_errors: Vec::new(),
+            equalizer: None,
+            audio_output: None,
+            audio_device: None,
+            audio_sink: None,
+            player_audio_sink: None,
+        })
+    }
+
+    /// Stops playback and lets go of the media, the player is kept for the next one.
     #[inline]
     pub fn stop(&mut self) {
-        unsafe {
-            if self.player != std::ptr::null_mut() {
-                libvlc_media_player_release(self.player);
-                self.player = std::ptr::null_mut();
-            }
-            if self.media != std::ptr::null_mut() {
-                libvlc_media_release(self.media);
-                self.media = std::ptr::null_mut();
-            }
+        if let Some(player) = &self.player {
+            player.stop();
+            player.set_media(None);
+        }
+        self.media = None;
+    }
+
+    /// Stops and drops the player, along with the outputs it was attached to.
+    fn release_player(&mut self) {
+        self.stop();
+        // clones held by subscriptions may keep the player, along with the renderer or
+        // sink its callbacks use
+        self.player = None;
+        self.player_audio_sink = None;
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(player) = &self.player {
+            player.set_pause(true);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(player) = &self.player {
+            player.set_pause(false);
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if let Some(player) = &self.player {
+            player.pause();
+        }
+    }
+
+    /// Seeks to `time` milliseconds from the start.
+    pub fn seek_time(&mut self, time: i64) {
+        if let Some(player) = &self.player {
+            let time = match player.length() {
+                Some(length) => time.clamp(0, length),
+                None => time.max(0),
+            };
+            player.set_time(time);
+        }
+    }
+
+    /// Seeks `delta` milliseconds forward, or backward if negative.
+    pub fn seek_relative(&mut self, delta: i64) {
+        if let Some(time) = self.time() {
+            self.seek_time(time + delta);
+        }
+    }
+
+    /// Seeks to `position` in the range `0.0..=1.0`.
+    pub fn seek_position(&mut self, position: f32) {
+        if let Some(player) = &self.player {
+            player.set_position(position.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Seeks by a fraction of the whole media, e.g. `0.1` skips a tenth forward.
+    pub fn seek_relative_position(&mut self, delta: f32) {
+        if let Some(position) = self.position() {
+            self.seek_position(position + delta);
+        }
+    }
+
+    pub fn set_rate(&mut self, rate: f32) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_rate(rate),
+            None => Err(NeovideoError::NothingPlaying),
+        }
+    }
+
+    pub fn rate(&self) -> f32 {
+        self.player.as_ref().map_or(1.0, |p| p.rate())
+    }
+
+    pub fn next_frame(&mut self) {
+        if let Some(player) = &self.player {
+            player.next_frame();
+        }
+    }
+
+    /// Current time in milliseconds.
+    pub fn time(&self) -> Option<i64> {
+        self.player.as_ref().and_then(|p| p.time())
+    }
+
+    /// Media length in milliseconds.
+    pub fn length(&self) -> Option<i64> {
+        self.player.as_ref().and_then(|p| p.length())
+    }
+
+    pub fn position(&self) -> Option<f32> {
+        self.player.as_ref().and_then(|p| p.position())
+    }
+
+    pub fn state(&self) -> State {
+        self.player
+            .as_ref()
+            .map_or(State::NothingSpecial, |p| p.state())
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(&self.player, Some(p) if p.is_playing())
+    }
+
+    pub fn is_seekable(&self) -> bool {
+        matches!(&self.player, Some(p) if p.is_seekable())
+    }
+
+    pub fn can_pause(&self) -> bool {
+        matches!(&self.player, Some(p) if p.can_pause())
+    }
+
+    pub fn volume(&self) -> Option<i32> {
+        self.player.as_ref().and_then(|p| p.volume())
+    }
+
+    pub fn set_volume(&mut self, volume: i32) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_volume(volume),
+            None => Err(NeovideoError::NothingPlaying),
+        }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        matches!(&self.player, Some(p) if p.is_muted())
+    }
+
+    pub fn set_mute(&mut self, mute: bool) {
+        if let Some(player) = &self.player {
+            player.set_mute(mute);
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        if let Some(player) = &self.player {
+            player.toggle_mute();
+        }
+    }
+
+    /// Audio delay in milliseconds, positive values play the sound later.
+    pub fn audio_delay(&self) -> i64 {
+        self.player.as_ref().map_or(0, |p| p.audio_delay())
+    }
+
+    pub fn set_audio_delay(&mut self, delay: i64) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_audio_delay(delay),
+            None => Err(NeovideoError::NothingPlaying),
+        }
+    }
+
+    /// The stereo mode, `None` while there is no audio output.
+    pub fn channel(&self) -> Option<AudioChannel> {
+        self.player.as_ref().and_then(|p| p.channel())
+    }
+
+    pub fn set_channel(&mut self, channel: AudioChannel) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_channel(channel),
+            None => Err(NeovideoError::NothingPlaying),
+        }
+    }
+
+    /// The selectable tracks of `track_type`, see `MediaPlayer::track_descriptions`.
+    pub fn track_descriptions(&self, track_type: TrackType) -> Vec<TrackDescription> {
+        self.player
+            .as_ref()
+            .map_or_else(Vec::new, |p| p.track_descriptions(track_type))
+    }
+
+    pub fn track(&self, track_type: TrackType) -> Option<i32> {
+        self.player.as_ref().and_then(|p| p.track(track_type))
+    }
+
+    /// Selects a track of the current media, `None` disables the type.
+    pub fn set_track(&mut self, track_type: TrackType, id: Option<i32>) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_track(track_type, id),
+            None => Err(NeovideoError::NothingPlaying),
+        }
+    }
+
+    /// Codec details of the tracks of the current media.
+    pub fn tracks(&self) -> Vec<Track> {
+        self.media.as_ref().map_or_else(Vec::new, |m| m.tracks())
+    }
+
+    /// The libvlc instance media are created with.
+    pub fn instance(&self) -> &Instance {
+        &self.vlc
+    }
+
+    /// The player, created by the first `play` and reused for later media unless the
+    /// audio sink changed in between.
+    pub fn media_player(&self) -> Option<&MediaPlayer> {
+        self.player.as_ref()
+    }
+
+    pub fn media(&self) -> Option<&Media> {
+        self.media.as_ref()
+    }
+
+    /// Size of the decoded picture in pixels, `None` before the first frame.
+    #[inline]
+    pub fn video_size(&self) -> Option<(u32, u32)> {
+        match (&self.software, &self.gl_output) {
+            (Some(software), _) => software.video_size(),
+            (None, Some(output)) => output.video_size(),
+            (None, None) => None,
         }
     }
 
+    /// Colour of the stream rendered by the OpenGL output, `None` until the output is set
+    /// up. The software output gets its frames converted to sRGB by libvlc.
+    pub fn color_info(&self) -> Option<ColorInfo> {
+        self.gl_output.as_ref()?.color_info()
+    }
+
+    /// The latest decoded frame, `None` before the first one. `update` tells whether it
+    /// changed since the last call.
     #[inline]
-    pub fn get_video_frame(&mut self, update: &mut bool) -> u32 {
-        let mut is_update = self.update.lock().unwrap();
-        *update = *is_update;
-        if *update {
-            std::mem::swap(&mut self.idx_swap, &mut self.idx_display);
-            *is_update = false;
+    pub fn get_video_frame(&mut self, update: &mut bool) -> Option<VideoFrame<'_>> {
+        if let Some(software) = &mut self.software {
+            return software.get_video_frame(update).map(VideoFrame::Cpu);
+        }
+        let texture = match &mut self.gl_output {
+            Some(output) => output.texture(update),
+            None => 0,
+        };
+        match texture {
+            0 => None,
+            tex => Some(VideoFrame::Texture(tex)),
         }
-        self.textures[self.idx_display]
     }
 
     #[inline]
-    pub fn play_media<T: AsRef<std::path::Path>>(&mut self, url: T) -> std::result::Result<(), ()> {
-        use std::ffi::CString;
-        let url = CString::new(url.as_ref().as_os_str().to_str().unwrap()).unwrap();
-        unsafe {
-            self.media = libvlc_media_new_location(self.vlc, url.as_ptr());
-            if self.media == std::ptr::null_mut() {
-                return Err(());
+    pub fn play_media<T: AsRef<std::path::Path>>(&mut self, url: T) -> Result<()> {
+        self.play_media_with_options::<T, &str>(url, &[])
+    }
+
+    /// Plays a local path or an MRL, applying input options such as `:start-time=10`.
+    pub fn play_media_with_options<T, S>(&mut self, url: T, options: &[S]) -> Result<()>
+    where
+        T: AsRef<std::path::Path>,
+        S: AsRef<str>,
+    {
+        let media = Media::new_url(&self.vlc, url)?;
+        self.play_with_options(&media, options)
+    }
+
+    /// Plays `media`, such as a playlist item, with input options and sidecar subtitles.
+    /// Both stay with `media`, an option replaces the earlier one of the same name when
+    /// it is played again.
+    pub fn play_with_options<S: AsRef<str>>(&mut self, media: &Media, options: &[S]) -> Result<()> {
+        let mut sub_file = false;
+        for option in options {
+            media.set_option(option.as_ref());
+            sub_file |= option.as_ref().starts_with(":sub-file=");
+        }
+        self.sidecar_errors.clear();
+        // only local files have a directory to look in
+        let path = media.mrl().and_then(|mrl| mrl_to_path(&mrl));
+        if let Some(path) = path.filter(|_| self.sidecar_subtitles && !sub_file) {
+            let mut added = false;
+            // a broken subtitle file is no reason not to play the media
+            for subtitle in find_sidecar_subtitles(path) {
+                match media.add_subtitle_file(subtitle) {
+                    Ok(()) => added = true,
+                    Err(e) => self.sidecar_errors.push(e),
+                }
             }
-            self.player = libvlc_media_player_new_from_media(self.media);
-            if self.player == std::ptr::null_mut() {
-                libvlc_media_release(self.media);
-                return Err(());
+            if added {
+                // libvlc would find the same files again
+                media.set_option(":no-sub-autodetect-file");
             }
+        }
+        self.play(media)
+    }
 
-            libvlc_video_set_output_callbacks(
-                self.player,
-                libvlc_video_engine_t::libvlc_video_engine_opengl,
-                Some(VLCVideo::setup),
-                Some(VLCVideo::cleanup),
-                None,
-                Some(VLCVideo::resize),
-                Some(VLCVideo::swap),
-                Some(VLCVideo::make_current),
-                Some(VLCVideo::get_proc_address),
-                None,
-                None,
-                self as *mut _ as *mut c_void,
-            );
+    pub fn sidecar_subtitles(&self) -> bool {
+        self.sidecar_subtitles
+    }
 
-            libvlc_media_player_play(self.player);
-            Ok(())
+    /// Loads subtitle files named after local media along with them, from the next
+    /// `play_media` on. Skipped when the options already name a `:sub-file`.
+    pub fn set_sidecar_subtitles(&mut self, enabled: bool) {
+        self.sidecar_subtitles = enabled;
+    }
+
+    /// Sidecar subtitles of the current media that could not be loaded, it plays
+    /// without them.
+    pub fn sidecar_errors(&self) -> &[NeovideoError] {
+        &self.sidecar_errors
+    }
+
+    /// Loads a subtitle file into the current media and switches to it.
+    pub fn load_subtitle_file<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        match &self.player {
+            Some(player) => player.add_subtitle_file(path, true),
+            None => Err(NeovideoError::NothingPlaying),
         }
     }
 
-    unsafe extern "C" fn setup(
-        data: *mut *mut c_void,
-        _cfg: *const libvlc_video_setup_device_cfg_t,
-        _out: *mut libvlc_video_setup_device_info_t,
-    ) -> bool {
-        let that = &mut *(data as *mut VLCVideo);
-        that.width = 0;
-        that.height = 0;
-        true
+    /// Subtitle delay in milliseconds, positive values show subtitles later.
+    pub fn subtitle_delay(&self) -> i64 {
+        self.player.as_ref().map_or(0, |p| p.subtitle_delay())
     }
 
-    unsafe extern "C" fn cleanup(data: *mut c_void) {
-        let that = &mut *(data as *mut VLCVideo);
-        if that.height == 0 && that.width == 0 {
-            return;
+    pub fn set_subtitle_delay(&mut self, delay: i64) -> Result<()> {
+        match &self.player {
+            Some(player) => player.set_subtitle_delay(delay),
+            None => Err(NeovideoError::NothingPlaying),
         }
-        gl::DeleteTextures(3, that.textures.as_mut_ptr());
-        gl::DeleteFramebuffers(3, that.fbo.as_mut_ptr());
     }
 
-    unsafe extern "C" fn swap(data: *mut c_void) {
-        let that = &mut *(data as *mut VLCVideo);
-        let mut is_update = that.update.lock().unwrap();
-        *is_update = true;
-        std::mem::swap(&mut that.idx_swap, &mut that.idx_render);
-        gl::BindFramebuffer(gl::FRAMEBUFFER, that.fbo[that.idx_render]);
+    pub fn equalizer(&self) -> Option<&Equalizer> {
+        self.equalizer.as_ref()
     }
 
-    unsafe extern "C" fn make_current(data: *mut c_void, current: bool) -> bool {
-        let that = &mut *(data as *mut VLCVideo);
-        if current {
-            match that.shared_context.take() {
-                Some(SharedContext::NotCurrent(n)) => {
-                    that.shared_context = Some(SharedContext::Current(n.make_current().unwrap()));
-                }
-                Some(SharedContext::Current(_)) => panic!("should not be current"),
-                None => panic!("should not be None"),
+    /// Applies `equalizer` to the playing media and all media played later, `None`
+    /// disables equalization.
+    pub fn set_equalizer(&mut self, equalizer: Option<Equalizer>) -> Result<()> {
+        self.equalizer = equalizer;
+        match &self.player {
+            Some(player) => player.set_equalizer(self.equalizer.as_ref()),
+            None => Ok(()),
+        }
+    }
+
+    /// The audio output modules libvlc offers.
+    pub fn audio_outputs(&self) -> Vec<AudioOutput> {
+        self.vlc.audio_outputs()
+    }
+
+    /// The chosen audio output module, `None` for the libvlc default.
+    pub fn audio_output(&self) -> Option<&str> {
+        self.audio_output.as_deref()
+    }
+
+    /// Chooses the audio output module for the next media and resets the device to the
+    /// module's default.
+    pub fn set_audio_output(&mut self, output: Option<&str>) -> Result<()> {
+        if let Some(output) = output {
+            if !self.audio_outputs().iter().any(|o| o.name == output) {
+                return Err(NeovideoError::InvalidArgument(format!(
+                    "no audio output '{}'",
+                    output
+                )));
             }
-        } else {
-            match that.shared_context.take() {
-                Some(SharedContext::NotCurrent(_)) => panic!("should be current"),
-                Some(SharedContext::Current(c)) => {
-                    that.shared_context =
-                        Some(SharedContext::NotCurrent(c.make_not_current().unwrap()));
-                }
-                None => panic!("should not be None"),
+        }
+        self.audio_output = output.map(str::to_owned);
+        self.audio_device = None;
+        Ok(())
+    }
+
+    /// The devices of the chosen audio output module, or of the default one once a
+    /// media was played.
+    pub fn audio_devices(&self) -> Vec<AudioDevice> {
+        match (&self.audio_output, &self.player) {
+            (Some(output), _) => self.vlc.audio_devices(output),
+            (None, Some(player)) => player.audio_devices(),
+            (None, None) => Vec::new(),
+        }
+    }
+
+    /// The chosen audio device, `None` for the default. It may be missing from
+    /// `audio_devices` when it was unplugged, the default device plays then.
+    pub fn audio_device(&self) -> Option<&str> {
+        self.audio_device.as_deref()
+    }
+
+    /// Chooses device `id` of the chosen audio output, `None` goes back to the default
+    /// device from the next media on. A device that is not present is used once it is
+    /// back, the default device plays until then.
+    pub fn set_audio_device(&mut self, id: Option<&str>) -> Result<()> {
+        if let (Some(id), Some(player)) = (id, &self.player) {
+            if player.audio_devices().iter().any(|d| d.id == id) {
+                player.set_audio_device(id)?;
             }
         }
-        true
+        self.audio_device = id.map(str::to_owned);
+        Ok(())
     }
 
-    unsafe extern "C" fn get_proc_address(data: *mut c_void, current: *const i8) -> *mut c_void {
-        let that = &*(data as *mut VLCVideo);
-        let s = CStr::from_ptr(current).to_str().unwrap();
-        match &that.shared_context {
-            Some(SharedContext::Current(c)) => {
-                let addr = c.get_proc_address(s) as *mut c_void;
-                return addr;
+    /// Applies the chosen audio output and device before a media starts, falling back to
+    /// the defaults if they are gone. They stay chosen, so they are used again once back.
+    fn apply_audio_output(&self, player: &MediaPlayer) {
+        if let Some(output) = &self.audio_output {
+            if player.set_audio_output(output).is_err() {
+                return;
             }
-            Some(SharedContext::NotCurrent(_)) => {
-                panic!("Should be current");
+        }
+        if let Some(id) = &self.audio_device {
+            if player.audio_devices().iter().any(|d| d.id == *id) {
+                let _ = player.set_audio_device(id);
             }
-            None => panic!("should not be None"),
         }
     }
 
-    unsafe extern "C" fn resize(
-        data: *mut c_void,
-        cfg: *const libvlc_video_render_cfg_t,
-        render_cfg: *mut libvlc_video_output_cfg_t,
-    ) -> bool {
-        let that = &mut *(data as *mut VLCVideo);
-        let cfg = &*(cfg);
-        let render_cfg = &mut *render_cfg;
-        if cfg.width != that.width || cfg.height != that.height {
-            VLCVideo::cleanup(data);
-        }
-        gl::GenTextures(3, that.textures.as_mut_ptr());
-        gl::GenFramebuffers(3, that.fbo.as_mut_ptr());
-
-        for i in 0..3 {
-            glchk!(
-            gl::BindTexture(gl::TEXTURE_2D, that.textures[i]);
-            gl::TexImage2D(
-                gl::TEXTURE_2D,
-                0,
-                gl::RGBA as i32,
-                cfg.width as i32,
-                cfg.height as i32,
-                0,
-                gl::RGBA,
-                gl::UNSIGNED_BYTE,
-                std::ptr::null_mut(),
-            );
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-            gl::BindFramebuffer(gl::FRAMEBUFFER, that.fbo[i]);
-            gl::FramebufferTexture2D(
-                gl::FRAMEBUFFER,
-                gl::COLOR_ATTACHMENT0,
-                gl::TEXTURE_2D,
-                that.textures[i],
-                0,
-            );
-            );
+    /// Sends the decoded audio of the next media on to `sink` instead of the audio
+    /// output, `None` goes back to the audio output.
+    pub fn set_audio_sink(&mut self, sink: Option<Box<dyn AudioSink>>) {
+        self.audio_sink = sink.map(SinkSlot::new);
+    }
+
+    pub fn has_audio_sink(&self) -> bool {
+        self.audio_sink.is_some()
+    }
+
+    /// Calls `notifier` on the decoding thread whenever a new frame is ready, so that
+    /// a window only needs to redraw then. `None` removes it.
+    pub fn set_frame_notifier(&mut self, notifier: Option<FrameNotifier>) {
+        if let Some(software) = &mut self.software {
+            software.set_frame_notifier(notifier);
+        } else if let Some(output) = &self.gl_output {
+            output.set_frame_notifier(notifier);
         }
-        gl::BindTexture(gl::TEXTURE_2D, 0);
-        let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
-        if status != gl::FRAMEBUFFER_COMPLETE {
-            panic!("FRAMEBUFFER STATUS NOT COMPLETE");
-        }
-        that.width = cfg.width;
-        that.height = cfg.height;
-        glchk!(gl::BindFramebuffer(gl::FRAMEBUFFER, that.fbo[that.idx_render]););
-        render_cfg.u.opengl_format = gl::RGBA as i32;
-        render_cfg.colorspace = libvlc_video_color_space_t::libvlc_video_colorspace_BT709;
-        render_cfg.primaries = libvlc_video_color_primaries_t::libvlc_video_primaries_BT709;
-        render_cfg.transfer = libvlc_video_transfer_func_t::libvlc_video_transfer_func_SRGB;
-        render_cfg.orientation = libvlc_video_orient_t::libvlc_video_orient_top_left;
-        true
+    }
+
+    /// Plays `media`, replacing whatever was playing.
+    pub fn play(&mut self, media: &Media) -> Result<()> {
+        // libvlc cannot take the audio callbacks back, another sink needs a new player
+        let same_sink = match (&self.audio_sink, &self.player_audio_sink) {
+            (Some(sink), Some(attached)) => Arc::ptr_eq(sink, attached),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same_sink {
+            self.release_player();
+        }
+        self.stop();
+        let player = match &self.player {
+            Some(player) => player.clone(),
+            None => self.create_player()?,
+        };
+        player.set_media(Some(media));
+        self.media = Some(media.clone());
+        if self.equalizer.is_some() {
+            player.set_equalizer(self.equalizer.as_ref())?;
+        }
+        self.apply_audio_output(&player);
+        player.play()
+    }
+
+    /// Creates the player all media are played with, attached to the outputs.
+    fn create_player(&mut self) -> Result<MediaPlayer> {
+        let player = MediaPlayer::new(&self.vlc)?;
+        if let Some(software) = &mut self.software {
+            software.attach(&player);
+        } else if let Some(output) = &self.gl_output {
+            output.attach(&player);
+        }
+        if let Some(sink) = &self.audio_sink {
+            SinkSlot::attach(sink, &player);
+        }
+        self.player_audio_sink = self.audio_sink.clone();
+        self.player = Some(player.clone());
+        Ok(player)
     }
 }
@@ -0,0 +1,86 @@
+use std::sync::atomic::Ordering;
+
+use neovideo_vlc::{AudioFormat, AudioSink, LevelMeter, NullSink, WavWriter};
+
+const STEREO: AudioFormat = AudioFormat {
+    rate: 48000,
+    channels: 2,
+};
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn null_sink_counts_frames() {
+    let mut sink = NullSink::new();
+    let frames = sink.frame_counter();
+    assert!(sink.start(STEREO));
+    sink.play(&[0; 960], 0);
+    sink.play(&[0; 40], 10_000);
+    sink.stop();
+    assert_eq!(frames.load(Ordering::Relaxed), 500);
+}
+
+#[test]
+fn level_meter_measures_channels() {
+    let mut meter = LevelMeter::new();
+    let levels = meter.levels();
+    assert!(levels.get().is_empty());
+    assert!(meter.start(STEREO));
+    // full scale square wave on the left, silence on the right
+    let samples: Vec<i16> = (0..100)
+        .flat_map(|i| [if i % 2 == 0 { i16::MAX } else { -i16::MAX }, 0])
+        .collect();
+    meter.play(&samples, 0);
+    let channels = levels.get();
+    assert_eq!(channels.len(), 2);
+    assert!((channels[0].peak - 1.0).abs() < 0.001);
+    assert!((channels[0].rms - 1.0).abs() < 0.001);
+    assert!(channels[0].peak_db().abs() < 0.01);
+    assert_eq!(channels[1].peak, 0.0);
+    assert_eq!(channels[1].rms_db(), f32::NEG_INFINITY);
+    meter.stop();
+    assert!(levels.get().is_empty());
+}
+
+#[test]
+fn wav_writer_writes_header_and_samples() {
+    let path = std::env::temp_dir().join(format!("neovideo-sink-{}.wav", std::process::id()));
+    {
+        let mut writer = WavWriter::create(&path).unwrap();
+        assert!(writer.start(STEREO));
+        writer.play(&[1, -1, 2, -2], 0);
+        writer.stop();
+        // a second media in the same format goes on in the same file
+        assert!(writer.start(STEREO));
+        assert!(!writer.start(AudioFormat {
+            rate: 44100,
+            channels: 2,
+        }));
+        writer.play(&[3, -3], 0);
+    }
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+    assert_eq!(&bytes[8..16], b"WAVEfmt ");
+    assert_eq!(u16_at(&bytes, 20), 1);
+    assert_eq!(u16_at(&bytes, 22), 2);
+    assert_eq!(u32_at(&bytes, 24), 48000);
+    assert_eq!(u32_at(&bytes, 28), 48000 * 4);
+    assert_eq!(u16_at(&bytes, 32), 4);
+    assert_eq!(u16_at(&bytes, 34), 16);
+    assert_eq!(&bytes[36..40], b"data");
+    assert_eq!(u32_at(&bytes, 40), 12);
+    let samples: Vec<i16> = bytes[44..]
+        .chunks(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]))
+        .collect();
+    assert_eq!(samples, [1, -1, 2, -2, 3, -3]);
+}
@@ -0,0 +1,144 @@
+use neovideo_vlc::color::{pq_from_linear, HDR_PEAK, SDR_WHITE};
+use neovideo_vlc::{ColorInfo, Primaries, ToneMapping, Transfer};
+
+fn assert_close(actual: [[f32; 3]; 3], expected: [[f32; 3]; 3]) {
+    for (a, e) in actual.iter().flatten().zip(expected.iter().flatten()) {
+        assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn same_primaries_convert_to_identity() {
+    let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    assert_close(Primaries::Bt709.conversion_to(Primaries::Bt709), identity);
+    assert_close(Primaries::Bt2020.conversion_to(Primaries::Bt2020), identity);
+}
+
+#[test]
+fn bt2020_to_bt709_matches_bt2087() {
+    // ITU-R BT.2087, the matrix taking linear BT.2020 to linear BT.709
+    assert_close(
+        Primaries::Bt2020.conversion_to(Primaries::Bt709),
+        [
+            [1.6605, -0.5876, -0.0728],
+            [-0.1246, 1.1329, -0.0083],
+            [-0.0182, -0.1006, 1.1187],
+        ],
+    );
+}
+
+#[test]
+fn white_stays_white() {
+    for primaries in [Primaries::Bt601_525, Primaries::Bt2020, Primaries::DciP3] {
+        for row in primaries.conversion_to(Primaries::Bt709) {
+            assert!((row.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn hdr_and_wide_gamut_need_conversion() {
+    let sdr = ColorInfo::default();
+    assert!(!sdr.is_hdr());
+    assert!(!sdr.needs_conversion());
+
+    let hdr10 = ColorInfo {
+        primaries: Primaries::Bt2020,
+        transfer: Transfer::Pq,
+        bit_depth: 10,
+        ..ColorInfo::default()
+    };
+    assert!(hdr10.is_hdr());
+    assert!(hdr10.needs_conversion());
+    assert_eq!(hdr10.describe(), "HDR10 (PQ, BT.2020, 10 bit)");
+
+    let wide = ColorInfo {
+        primaries: Primaries::DciP3,
+        ..ColorInfo::default()
+    };
+    assert!(!wide.is_hdr());
+    assert!(wide.needs_conversion());
+}
+
+fn nits(transfer: Transfer, code: f32) -> f32 {
+    transfer.to_linear([code; 3])[1] * SDR_WHITE
+}
+
+#[test]
+fn pq_decodes_to_absolute_luminance() {
+    // SMPTE ST 2084 reference values
+    assert_eq!(nits(Transfer::Pq, 0.0), 0.0);
+    assert!((nits(Transfer::Pq, 1.0) - 10000.0).abs() < 1.0);
+    assert!((nits(Transfer::Pq, 0.5) - 92.25).abs() < 0.1);
+    assert!((nits(Transfer::Pq, 0.75) - 983.0).abs() < 1.0);
+    // ITU-R BT.2408, SDR white sits at 58% PQ
+    assert!((pq_from_linear(1.0) - 0.5806).abs() < 1e-3);
+    for code in [0.1, 0.3, 0.58, 0.9] {
+        let linear = Transfer::Pq.to_linear([code; 3])[0];
+        assert!((pq_from_linear(linear) - code).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn hlg_decodes_for_the_assumed_display() {
+    assert_eq!(nits(Transfer::Hlg, 0.0), 0.0);
+    assert!((nits(Transfer::Hlg, 1.0) - HDR_PEAK).abs() < 1.0);
+    // ITU-R BT.2408, SDR white sits at 75% HLG on a 1000 nit display
+    assert!((nits(Transfer::Hlg, 0.75) - 203.0).abs() < 1.0);
+    // the two halves of the inverse OETF meet at 0.5
+    let below = nits(Transfer::Hlg, 0.4999);
+    let above = nits(Transfer::Hlg, 0.5001);
+    assert!((above - below).abs() < 0.1);
+}
+
+#[test]
+fn tone_mapping_keeps_black_and_lands_the_peak_on_white() {
+    let peak = HDR_PEAK / SDR_WHITE;
+    for tone_mapping in ToneMapping::ALL {
+        let black = tone_mapping.apply([0.0; 3], peak);
+        assert!(black.iter().all(|c| c.abs() < 1e-3), "{:?}", tone_mapping);
+        let white = tone_mapping.apply([peak; 3], peak);
+        assert!(
+            white.iter().all(|c| (c - 1.0).abs() < 1e-3),
+            "{:?} maps the peak to {:?}",
+            tone_mapping,
+            white
+        );
+    }
+}
+
+#[test]
+fn tone_mapping_is_monotonic() {
+    let peak = HDR_PEAK / SDR_WHITE;
+    for tone_mapping in ToneMapping::ALL {
+        let mut last = -1.0;
+        for step in 0..=100 {
+            let x = peak * step as f32 / 100.0;
+            let y = tone_mapping.apply([x; 3], peak)[0];
+            // BT.2390 flattens out towards the peak
+            assert!(y >= last, "{:?} at {}", tone_mapping, x);
+            last = y;
+        }
+    }
+}
+
+#[test]
+fn bt2390_leaves_the_midtones_alone() {
+    let peak = HDR_PEAK / SDR_WHITE;
+    for x in [0.01, 0.1, 0.18] {
+        let y = ToneMapping::Bt2390.apply([x; 3], peak)[0];
+        assert!((y - x).abs() < 1e-3, "{} became {}", x, y);
+    }
+    // Reinhard compresses everywhere
+    assert!(ToneMapping::Reinhard.apply([0.18; 3], peak)[0] < 0.18);
+    // hue is kept by scaling all channels alike
+    let [r, g, b] = ToneMapping::Bt2390.apply([4.0, 2.0, 1.0], peak);
+    assert!((r / g - 2.0).abs() < 1e-3 && (g / b - 2.0).abs() < 1e-3);
+}
+
+#[test]
+fn sdr_sources_are_not_tone_mapped() {
+    for tone_mapping in ToneMapping::ALL {
+        assert_eq!(tone_mapping.apply([0.5, 1.5, 0.2], 1.0), [0.5, 1.5, 0.2]);
+    }
+}
@@ -0,0 +1,36 @@
+//! Helpers shared by the integration tests, each test crate uses a part of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use neovideo_vlc::{Instance, NeovideoError};
+
+/// libvlc and its plugins are installed separately, without them there is nothing to
+/// test. Returns the value of `result`, or prints why the test is skipped.
+pub fn or_skip<T>(result: Result<T, NeovideoError>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("skipped, libvlc is not available: {}", e);
+            None
+        }
+    }
+}
+
+pub fn libvlc_missing() -> bool {
+    or_skip(Instance::new()).is_none()
+}
+
+/// A path in the temporary directory, unique to `name` and the test process.
+pub fn fixture_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("neovideo-test-{}-{}", std::process::id(), name))
+}
+
+/// Writes a binary PPM picture of one colour, which libvlc shows as a still video.
+pub fn write_ppm(path: &Path, (width, height): (u32, u32), rgb: [u8; 3]) {
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for _ in 0..width * height {
+        data.extend_from_slice(&rgb);
+    }
+    std::fs::write(path, data).unwrap();
+}
@@ -0,0 +1,51 @@
+use neovideo_vlc::{Equalizer, NeovideoError};
+
+mod common;
+
+use common::or_skip;
+
+#[test]
+fn try_clone_copies_the_settings() {
+    let mut equalizer = match or_skip(Equalizer::new()) {
+        Some(equalizer) => equalizer,
+        None => return,
+    };
+    equalizer.set_preamp(-3.5).unwrap();
+    let bands = Equalizer::band_count();
+    for band in 0..bands {
+        equalizer.set_amp_at_index(band as f32, band).unwrap();
+    }
+    assert!(matches!(
+        equalizer.set_amp_at_index(1.0, bands),
+        Err(NeovideoError::InvalidArgument(_))
+    ));
+
+    let copy = equalizer.try_clone().unwrap();
+    assert_ne!(copy.raw(), equalizer.raw());
+    assert_eq!(copy.preamp(), -3.5);
+    assert_eq!(copy.amps(), equalizer.amps());
+
+    // the copy is independent
+    drop(equalizer);
+    assert_eq!(copy.amp_at_index(1), 1.0);
+}
+
+#[test]
+fn preamp_is_clamped() {
+    let mut equalizer = match or_skip(Equalizer::new()) {
+        Some(equalizer) => equalizer,
+        None => return,
+    };
+    equalizer.set_preamp(40.0).unwrap();
+    assert_eq!(equalizer.preamp(), 20.0);
+    equalizer.set_preamp(-40.0).unwrap();
+    assert_eq!(equalizer.preamp(), -20.0);
+}
+
+#[test]
+fn unknown_presets_are_rejected() {
+    assert!(matches!(
+        Equalizer::from_preset(Equalizer::preset_count()),
+        Err(NeovideoError::InvalidArgument(_))
+    ));
+}
@@ -0,0 +1,57 @@
+use neovideo_vlc::vlc::{libvlc_event_t, EventType, Meta, State};
+use neovideo_vlc::Event;
+
+fn raw_event(ty: i32, fill: impl FnOnce(&mut libvlc_event_t)) -> libvlc_event_t {
+    // a zeroed event is valid, every field is an int, float or pointer
+    let mut event: libvlc_event_t = unsafe { std::mem::zeroed() };
+    event._type = ty;
+    fill(&mut event);
+    event
+}
+
+fn decode(event: &libvlc_event_t) -> Event {
+    unsafe { Event::from_raw(event) }
+}
+
+#[test]
+fn decodes_known_payloads() {
+    let event = raw_event(EventType::MediaMetaChanged as i32, |e| {
+        e.u.media_meta_changed.meta_type = Meta::Artist as i32
+    });
+    assert_eq!(decode(&event), Event::MediaMetaChanged(Meta::Artist));
+
+    let event = raw_event(EventType::MediaStateChanged as i32, |e| {
+        e.u.media_state_changed.new_state = 3
+    });
+    assert_eq!(decode(&event), Event::MediaStateChanged(State::Playing));
+
+    let event = raw_event(EventType::MediaPlayerTimeChanged as i32, |e| {
+        e.u.media_player_time_changed.new_time = 1500
+    });
+    assert_eq!(decode(&event), Event::TimeChanged(1500));
+}
+
+#[test]
+fn unknown_values_are_kept_raw() {
+    let ty = EventType::MediaMetaChanged as i32;
+    let event = raw_event(ty, |e| e.u.media_meta_changed.meta_type = 1000);
+    assert_eq!(decode(&event), Event::Unknown(ty));
+
+    let ty = EventType::MediaStateChanged as i32;
+    let event = raw_event(ty, |e| e.u.media_state_changed.new_state = -1);
+    assert_eq!(decode(&event), Event::Unknown(ty));
+
+    assert_eq!(decode(&raw_event(0x7fff, |_| ())), Event::Unknown(0x7fff));
+}
+
+#[test]
+fn raw_values_round_trip() {
+    for meta in Meta::ALL {
+        assert_eq!(Meta::from_raw(meta as i32), Some(meta));
+    }
+    for raw in 0..8 {
+        assert_eq!(State::from_raw(raw).map(|state| state as i32), Some(raw));
+    }
+    assert_eq!(Meta::from_raw(-1), None);
+    assert_eq!(State::from_raw(8), None);
+}
@@ -0,0 +1,102 @@
+use neovideo_vlc::{AspectRatio, ScaleMode, VideoQuad};
+
+const FULL_UV: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+fn layout(video_size: (u32, u32), viewport: (f32, f32), mode: ScaleMode) -> VideoQuad {
+    VideoQuad::new(video_size, viewport, mode, AspectRatio::Source).unwrap()
+}
+
+fn assert_close(actual: [f32; 4], expected: [f32; 4]) {
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn fit_letterboxes_wide_video() {
+    let quad = layout((1920, 1080), (800.0, 800.0), ScaleMode::Fit);
+    assert_close(quad.rect, [0.0, 175.0, 800.0, 625.0]);
+    assert_close(quad.uv, FULL_UV);
+}
+
+#[test]
+fn fit_pillarboxes_narrow_video() {
+    let quad = layout((640, 480), (1600.0, 900.0), ScaleMode::Fit);
+    assert_close(quad.rect, [200.0, 0.0, 1400.0, 900.0]);
+    assert_close(quad.uv, FULL_UV);
+}
+
+#[test]
+fn fill_crops_instead_of_adding_bars() {
+    // 16:9 in a square, the picture is 1422.2 wide of which 800 show
+    let quad = layout((1920, 1080), (800.0, 800.0), ScaleMode::Fill);
+    assert_close(quad.rect, [0.0, 0.0, 800.0, 800.0]);
+    assert_close(quad.uv, [0.21875, 0.0, 0.78125, 1.0]);
+
+    let quad = layout((640, 480), (1600.0, 900.0), ScaleMode::Fill);
+    assert_close(quad.rect, [0.0, 0.0, 1600.0, 900.0]);
+    assert_close(quad.uv, [0.0, 0.125, 1.0, 0.875]);
+}
+
+#[test]
+fn stretch_covers_the_viewport() {
+    let quad = layout((640, 480), (1600.0, 900.0), ScaleMode::Stretch);
+    assert_close(quad.rect, [0.0, 0.0, 1600.0, 900.0]);
+    assert_close(quad.uv, FULL_UV);
+}
+
+#[test]
+fn zoom_scales_the_fitted_picture() {
+    let quad = layout((1920, 1080), (1920.0, 1080.0), ScaleMode::Zoom(2.0));
+    assert_close(quad.rect, [0.0, 0.0, 1920.0, 1080.0]);
+    assert_close(quad.uv, [0.25, 0.25, 0.75, 0.75]);
+
+    let quad = layout((1920, 1080), (1920.0, 1080.0), ScaleMode::Zoom(0.5));
+    assert_close(quad.rect, [480.0, 270.0, 1440.0, 810.0]);
+    assert_close(quad.uv, FULL_UV);
+}
+
+#[test]
+fn forced_aspect_ratio_overrides_the_picture() {
+    let quad = VideoQuad::new(
+        (1920, 1080),
+        (1600.0, 900.0),
+        ScaleMode::Fit,
+        AspectRatio::Standard,
+    )
+    .unwrap();
+    assert_close(quad.rect, [200.0, 0.0, 1400.0, 900.0]);
+
+    let quad = VideoQuad::new(
+        (640, 480),
+        (1000.0, 1000.0),
+        ScaleMode::Fit,
+        AspectRatio::Scope,
+    )
+    .unwrap();
+    let height = 1000.0 / 2.35;
+    let top = (1000.0 - height) / 2.0;
+    assert_close(quad.rect, [0.0, top, 1000.0, 1000.0 - top]);
+
+    let quad = VideoQuad::new(
+        (640, 480),
+        (1600.0, 900.0),
+        ScaleMode::Fit,
+        AspectRatio::Widescreen,
+    )
+    .unwrap();
+    assert_close(quad.rect, [0.0, 0.0, 1600.0, 900.0]);
+}
+
+#[test]
+fn empty_sizes_have_no_quad() {
+    let new = |video_size, viewport, aspect_ratio| {
+        VideoQuad::new(video_size, viewport, ScaleMode::Fit, aspect_ratio)
+    };
+    assert!(new((0, 0), (800.0, 600.0), AspectRatio::Source).is_none());
+    assert!(new((1920, 0), (800.0, 600.0), AspectRatio::Source).is_none());
+    assert!(new((1920, 1080), (0.0, 600.0), AspectRatio::Source).is_none());
+    assert!(new((1920, 1080), (800.0, -1.0), AspectRatio::Widescreen).is_none());
+    // a forced aspect ratio does not need the picture size
+    assert!(new((0, 0), (800.0, 600.0), AspectRatio::Widescreen).is_some());
+}
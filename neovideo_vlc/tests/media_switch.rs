@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use glutin::{dpi::PhysicalSize, event_loop::EventLoop, ContextBuilder};
+use neovideo_vlc::swvideo::Chroma;
+use neovideo_vlc::vlc::State;
+use neovideo_vlc::vlcvideo::{VLCVideo, VideoFrame};
+use neovideo_vlc::{AudioFormat, AudioSink, NullSink, WavWriter};
+
+mod common;
+
+use common::{fixture_path, libvlc_missing, write_ppm};
+
+const WARM_UP: usize = 10;
+const SWITCHES: usize = 100;
+/// A little slack for threads libvlc keeps around.
+const HANDLE_SLACK: usize = 4;
+
+/// Writes `seconds` of silence as a WAV file, a media every libvlc build can open.
+fn silent_wav(seconds: u32) -> PathBuf {
+    let path = fixture_path("silence.wav");
+    let format = AudioFormat {
+        rate: 8000,
+        channels: 1,
+    };
+    let mut writer = WavWriter::create(&path).unwrap();
+    assert!(writer.start(format));
+    writer.play(&vec![0i16; (format.rate * seconds) as usize], 0);
+    writer.stop();
+    path
+}
+
+#[cfg(target_os = "linux")]
+fn open_handles() -> usize {
+    std::fs::read_dir("/proc/self/fd").unwrap().count()
+}
+
+#[cfg(target_os = "macos")]
+fn open_handles() -> usize {
+    std::fs::read_dir("/dev/fd").unwrap().count()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn open_handles() -> usize {
+    panic!("open handles cannot be counted on this platform");
+}
+
+fn play_until_started(video: &mut VLCVideo, path: &Path, options: &[&str]) {
+    video.play_media_with_options(path, options).unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !matches!(video.state(), State::Playing | State::Ended | State::Error) {
+        assert!(Instant::now() < deadline, "media did not start");
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(!matches!(video.state(), State::Error), "media failed");
+}
+
+#[test]
+fn switching_media_does_not_leak() {
+    if libvlc_missing() {
+        return;
+    }
+    let path = silent_wav(1);
+    let mut video = VLCVideo::new_software(Chroma::Rgba, &["--no-video"]).unwrap();
+    video.set_audio_sink(Some(Box::new(NullSink::new())));
+
+    // libvlc loads its modules and starts its threads with the first media
+    for _ in 0..WARM_UP {
+        play_until_started(&mut video, &path, &[]);
+    }
+    let player = video.media_player().unwrap().raw();
+    let handles = open_handles();
+    for _ in 0..SWITCHES {
+        play_until_started(&mut video, &path, &[]);
+    }
+    video.stop();
+
+    assert_eq!(video.media_player().unwrap().raw(), player);
+    assert!(video.media().is_none());
+    assert!(open_handles() <= handles + HANDLE_SLACK);
+    drop(video);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Waits for a new frame of the OpenGL output, returns its texture.
+fn next_texture(video: &mut VLCVideo) -> u32 {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let mut update = false;
+        if let (Some(VideoFrame::Texture(texture)), true) =
+            (video.get_video_frame(&mut update), update)
+        {
+            return texture;
+        }
+        assert!(Instant::now() < deadline, "no frame arrived");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn event_loop() -> EventLoop<()> {
+    use glutin::platform::unix::EventLoopExtUnix;
+    // the test harness runs tests off the main thread
+    EventLoop::new_any_thread()
+}
+
+#[cfg(windows)]
+fn event_loop() -> EventLoop<()> {
+    use glutin::platform::windows::EventLoopExtWindows;
+    EventLoop::new_any_thread()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", windows)))]
+fn event_loop() -> EventLoop<()> {
+    EventLoop::new()
+}
+
+#[test]
+fn gl_output_keeps_its_textures_across_media() {
+    if libvlc_missing() {
+        return;
+    }
+    // winit panics without a display server
+    let event_loop = match std::panic::catch_unwind(event_loop) {
+        Ok(event_loop) => event_loop,
+        Err(_) => {
+            eprintln!("skipped, no display to create a GL context on");
+            return;
+        }
+    };
+    let context = match ContextBuilder::new().build_headless(&event_loop, PhysicalSize::new(64, 64))
+    {
+        Ok(context) => unsafe { context.make_current().map_err(|(_, e)| e).unwrap() },
+        Err(e) => {
+            eprintln!("skipped, no GL context: {}", e);
+            return;
+        }
+    };
+    let path = fixture_path("red.ppm");
+    write_ppm(&path, (64, 48), [255, 0, 0]);
+    let options = [":image-duration=10"];
+    let mut video = VLCVideo::with_args(&context, &event_loop, &["--no-audio"]).unwrap();
+
+    for _ in 0..WARM_UP {
+        play_until_started(&mut video, &path, &options);
+        next_texture(&mut video);
+    }
+    let handles = open_handles();
+    let mut textures = HashSet::new();
+    for _ in 0..SWITCHES {
+        play_until_started(&mut video, &path, &options);
+        textures.insert(next_texture(&mut video));
+        assert_eq!(video.video_size(), Some((64, 48)));
+    }
+    video.stop();
+
+    // the three render targets of the triple buffer are reused by every media
+    assert!(textures.len() <= 3, "{} textures", textures.len());
+    assert!(open_handles() <= handles + HANDLE_SLACK);
+    drop(video);
+    std::fs::remove_file(&path).unwrap();
+}
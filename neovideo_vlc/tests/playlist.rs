@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use neovideo_vlc::{Instance, Playlist, Repeat};
+
+mod common;
+
+use common::{fixture_path, or_skip};
+
+/// A playlist of `len` items named by their original position, `None` without libvlc.
+fn playlist(len: usize) -> Option<Playlist> {
+    let instance = or_skip(Instance::new())?;
+    let mut playlist = Playlist::new(&instance).unwrap();
+    for i in 0..len {
+        assert_eq!(playlist.add_url(format!("test://{}", i)).unwrap(), i);
+    }
+    Some(playlist)
+}
+
+/// The original positions of the items in list order.
+fn names(playlist: &Playlist) -> Vec<usize> {
+    (0..playlist.len())
+        .map(|index| {
+            let mrl = playlist.get(index).unwrap().mrl().unwrap();
+            mrl["test://".len()..].parse().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn shuffle_plays_every_item_once() {
+    let mut playlist = match playlist(10) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    assert_eq!(playlist.set_current(4), Some(4));
+    playlist.set_shuffle(true);
+    let mut played = HashSet::new();
+    played.insert(4);
+    while let Some(index) = playlist.next_item() {
+        assert!(played.insert(index), "{} played twice", index);
+    }
+    assert_eq!(played.len(), 10);
+
+    // another round with repeat all, the last item is followed by a new order
+    playlist.set_repeat(Repeat::All);
+    let first = playlist.next_item().unwrap();
+    let mut round = vec![first];
+    for _ in 1..10 {
+        round.push(playlist.next_item().unwrap());
+    }
+    assert_eq!(round.iter().collect::<HashSet<_>>().len(), 10);
+}
+
+#[test]
+fn advance_follows_the_repeat_mode() {
+    let mut playlist = match playlist(3) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    assert_eq!(playlist.set_current(3), None);
+    assert_eq!(playlist.set_current(2), Some(2));
+
+    playlist.set_repeat(Repeat::One);
+    assert_eq!(playlist.advance(), Some(2));
+    assert_eq!(playlist.advance(), Some(2));
+
+    playlist.set_repeat(Repeat::All);
+    assert_eq!(playlist.advance(), Some(0));
+    assert_eq!(playlist.advance(), Some(1));
+    assert_eq!(playlist.previous_item(), Some(0));
+    assert_eq!(playlist.previous_item(), Some(2));
+
+    playlist.set_repeat(Repeat::Off);
+    assert_eq!(playlist.advance(), None);
+    assert_eq!(playlist.current(), None);
+    // starts over from the first item
+    assert_eq!(playlist.advance(), Some(0));
+}
+
+#[test]
+fn move_item_keeps_the_current_item() {
+    let mut playlist = match playlist(4) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    playlist.set_current(1);
+    assert!(playlist.move_item(1, 3));
+    assert_eq!(names(&playlist), [0, 2, 3, 1]);
+    assert_eq!(playlist.current(), Some(3));
+
+    assert!(playlist.move_item(0, 2));
+    assert_eq!(names(&playlist), [2, 3, 0, 1]);
+    assert_eq!(playlist.current(), Some(3));
+
+    assert!(playlist.move_item(3, 0));
+    assert_eq!(names(&playlist), [1, 2, 3, 0]);
+    assert_eq!(playlist.current(), Some(0));
+    // the play order follows the list
+    assert_eq!(playlist.next_item(), Some(1));
+
+    assert!(!playlist.move_item(0, 4));
+    assert!(!playlist.move_item(4, 0));
+    assert_eq!(names(&playlist), [1, 2, 3, 0]);
+}
+
+#[test]
+fn removing_the_current_item_continues_around_it() {
+    let mut playlist = match playlist(4) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    playlist.set_current(1);
+    assert!(playlist.remove(1));
+    assert_eq!(names(&playlist), [0, 2, 3]);
+    assert_eq!(playlist.current(), None);
+    // the item after the removed one took its place
+    assert_eq!(playlist.next_item(), Some(1));
+
+    assert!(playlist.remove(1));
+    assert_eq!(playlist.previous_item(), Some(0));
+
+    // removing others shifts the current item
+    playlist.set_current(1);
+    assert!(playlist.remove(0));
+    assert_eq!(names(&playlist), [3]);
+    assert_eq!(playlist.current(), Some(0));
+    assert!(!playlist.remove(1));
+}
+
+#[test]
+fn removing_before_a_removed_current_item_keeps_the_place() {
+    let mut playlist = match playlist(4) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    playlist.set_current(2);
+    assert!(playlist.remove(2));
+    assert!(playlist.remove(0));
+    assert_eq!(names(&playlist), [1, 3]);
+    assert_eq!(playlist.next_item(), Some(1));
+
+    playlist.set_current(1);
+    assert!(playlist.remove(1));
+    assert!(playlist.remove(0));
+    assert!(playlist.is_empty());
+    assert_eq!(playlist.previous_item(), None);
+}
+
+#[test]
+fn inserting_before_a_removed_current_item_keeps_the_place() {
+    let mut playlist = match playlist(3) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    playlist.set_current(1);
+    assert!(playlist.remove(1));
+    let media = playlist.get(1).unwrap();
+    assert!(playlist.insert(0, &media));
+    assert_eq!(names(&playlist), [2, 0, 2]);
+    assert_eq!(playlist.next_item(), Some(2));
+    assert_eq!(playlist.next_item(), None);
+}
+
+#[test]
+fn import_adds_all_items_or_none() {
+    let mut playlist = match playlist(1) {
+        Some(playlist) => playlist,
+        None => return,
+    };
+    let path = fixture_path("import.m3u");
+    // a NUL cannot be passed to libvlc
+    std::fs::write(&path, "test://1\ntest://2\0\ntest://3\n").unwrap();
+    assert!(playlist.import(&path).is_err());
+    assert_eq!(names(&playlist), [0]);
+
+    std::fs::write(&path, "test://1\ntest://2\n").unwrap();
+    assert_eq!(playlist.import(&path).unwrap(), 2);
+    assert_eq!(names(&playlist), [0, 1, 2]);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+
+use neovideo_vlc::playlist_file;
+use neovideo_vlc::{NeovideoError, PlaylistEntry, PlaylistFormat};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn entry(location: &str, title: Option<&str>, duration: Option<i64>) -> PlaylistEntry {
+    PlaylistEntry {
+        location: location.to_owned(),
+        title: title.map(str::to_owned),
+        duration,
+    }
+}
+
+/// The entries all three fixtures describe, with local paths resolved against the
+/// fixtures directory.
+fn sample_entries() -> Vec<PlaylistEntry> {
+    let local = |path: &str| fixture(path).to_string_lossy().into_owned();
+    vec![
+        entry(
+            &local("videos/big_buck_bunny.mp4"),
+            Some("Big Buck Bunny"),
+            Some(125_000),
+        ),
+        entry("https://example.com/live.m3u8", Some("Live news"), None),
+        entry(&local("/media/clips/intro.mkv"), None, None),
+    ]
+}
+
+/// Awkward but valid entries, durations in whole seconds as M3U and PLS store them.
+fn tricky_entries() -> Vec<PlaylistEntry> {
+    vec![
+        entry(
+            "videos/a b & c.mkv",
+            Some("Tom & Jerry <1950>"),
+            Some(421_000),
+        ),
+        entry(
+            "/srv/media/caf\u{e9} 100%.webm",
+            Some("Caf\u{e9}, \"quoted\""),
+            None,
+        ),
+        entry("https://example.com/stream?id=1&q=2", None, Some(0)),
+        entry("relative.ogv", None, None),
+    ]
+}
+
+#[test]
+fn detects_format_by_extension() {
+    assert_eq!(
+        PlaylistFormat::from_path("list.m3u"),
+        Some(PlaylistFormat::M3u)
+    );
+    assert_eq!(
+        PlaylistFormat::from_path("list.M3U8"),
+        Some(PlaylistFormat::M3u)
+    );
+    assert_eq!(
+        PlaylistFormat::from_path("list.pls"),
+        Some(PlaylistFormat::Pls)
+    );
+    assert_eq!(
+        PlaylistFormat::from_path("list.xspf"),
+        Some(PlaylistFormat::Xspf)
+    );
+    assert_eq!(PlaylistFormat::from_path("video.mkv"), None);
+    assert_eq!(PlaylistFormat::sniff("[Playlist]\n"), PlaylistFormat::Pls);
+    assert_eq!(
+        PlaylistFormat::sniff("<?xml version=\"1.0\"?>"),
+        PlaylistFormat::Xspf
+    );
+    assert_eq!(PlaylistFormat::sniff("video.mkv\n"), PlaylistFormat::M3u);
+}
+
+#[test]
+fn loads_fixtures() {
+    for name in ["sample.m3u", "sample.pls", "sample.xspf"] {
+        let entries = playlist_file::load(fixture(name)).unwrap();
+        assert_eq!(entries, sample_entries(), "{}", name);
+    }
+}
+
+#[test]
+fn fixtures_round_trip() {
+    for name in ["sample.m3u", "sample.pls", "sample.xspf"] {
+        let format = PlaylistFormat::from_path(name).unwrap();
+        let entries = playlist_file::load(fixture(name)).unwrap();
+        let written = format.write(&entries);
+        assert_eq!(format.parse(&written, None).unwrap(), entries, "{}", name);
+    }
+}
+
+#[test]
+fn tricky_entries_round_trip() {
+    for format in [
+        PlaylistFormat::M3u,
+        PlaylistFormat::Pls,
+        PlaylistFormat::Xspf,
+    ] {
+        let written = format.write(&tricky_entries());
+        assert_eq!(
+            format.parse(&written, None).unwrap(),
+            tricky_entries(),
+            "{:?}:\n{}",
+            format,
+            written
+        );
+    }
+}
+
+#[test]
+fn save_and_load() {
+    let dir = std::env::temp_dir().join(format!("neovideo-playlist-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["saved.m3u8", "saved.pls", "saved.xspf"] {
+        let path = dir.join(name);
+        playlist_file::save(&path, &tricky_entries()).unwrap();
+        let loaded = playlist_file::load(&path).unwrap();
+        // relative locations come back resolved against the playlist directory
+        let expected: Vec<PlaylistEntry> = tricky_entries()
+            .into_iter()
+            .map(|mut entry| {
+                if !entry.location.contains("://") {
+                    entry.location = dir.join(&entry.location).to_string_lossy().into_owned();
+                }
+                entry
+            })
+            .collect();
+        assert_eq!(loaded, expected, "{}", name);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reports_malformed_lines() {
+    let m3u = "#EXTM3U\n#EXTINF:abc,Title\nvideo.mkv\n";
+    assert!(matches!(
+        PlaylistFormat::M3u.parse(m3u, None),
+        Err(NeovideoError::PlaylistParse { line: 2, .. })
+    ));
+
+    let pls = "[playlist]\nFile1=a.mkv\nthis is not a pair\n";
+    assert!(matches!(
+        PlaylistFormat::Pls.parse(pls, None),
+        Err(NeovideoError::PlaylistParse { line: 3, .. })
+    ));
+
+    let xspf = "<playlist><trackList>\n<track>\n<title>No location</title>\n</track>\n</trackList></playlist>";
+    assert!(matches!(
+        PlaylistFormat::Xspf.parse(xspf, None),
+        Err(NeovideoError::PlaylistParse { line: 2, .. })
+    ));
+
+    assert!(matches!(
+        playlist_file::load(fixture("missing.m3u")),
+        Err(NeovideoError::Io { .. })
+    ));
+}
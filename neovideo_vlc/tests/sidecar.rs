@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use neovideo_vlc::sidecar::{find_sidecar_subtitles, is_subtitle_file};
+use neovideo_vlc::swvideo::Chroma;
+use neovideo_vlc::vlcvideo::VLCVideo;
+
+mod common;
+
+use common::{fixture_path, or_skip, write_ppm};
+
+/// A fresh directory holding empty files named `files`.
+fn dir_with(name: &str, files: &[&str]) -> PathBuf {
+    let dir = fixture_path(&format!("sidecar-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for file in files {
+        fs::write(dir.join(file), "").unwrap();
+    }
+    dir
+}
+
+fn names(paths: &[PathBuf]) -> Vec<&str> {
+    paths
+        .iter()
+        .map(|path| path.file_name().unwrap().to_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn recognizes_subtitle_extensions() {
+    assert!(is_subtitle_file("movie.srt"));
+    assert!(is_subtitle_file("movie.en.VTT"));
+    assert!(is_subtitle_file("dir/movie.Ass"));
+    assert!(!is_subtitle_file("movie.mkv"));
+    assert!(!is_subtitle_file("srt"));
+}
+
+#[test]
+fn finds_subtitles_named_after_the_media() {
+    let dir = dir_with(
+        "match",
+        &[
+            "movie.mkv",
+            "movie.forced.VTT",
+            "movie.en.srt",
+            "movie.srt",
+            "movie.txt",
+            "movies.srt",
+            "other.srt",
+            "Movie.de.srt",
+        ],
+    );
+    // directories are skipped even with a subtitle extension
+    fs::create_dir(dir.join("movie.sub")).unwrap();
+
+    let found = find_sidecar_subtitles(dir.join("movie.mkv"));
+    // the exact match first, then by name; stems match case-sensitively
+    assert_eq!(
+        names(&found),
+        ["movie.srt", "movie.en.srt", "movie.forced.VTT"]
+    );
+    assert!(found
+        .iter()
+        .all(|path| path.parent() == Some(dir.as_path())));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_subtitle_is_not_its_own_sidecar() {
+    let dir = dir_with("self", &["talk.srt", "talk.en.srt"]);
+    let found = find_sidecar_subtitles(dir.join("talk.srt"));
+    assert_eq!(names(&found), ["talk.en.srt"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_directory_has_no_sidecars() {
+    let dir = dir_with("missing", &[]);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(find_sidecar_subtitles(dir.join("movie.mkv")).is_empty());
+    assert!(find_sidecar_subtitles(Path::new("/")).is_empty());
+}
+
+#[test]
+fn sidecars_are_attached_to_file_mrls() {
+    let mut video = match or_skip(VLCVideo::new_software(Chroma::Rgba, &["--no-audio"])) {
+        Some(video) => video,
+        None => return,
+    };
+    let dir = dir_with("mrl", &[]);
+    let subtitle = dir.join("clip.srt");
+    fs::write(&subtitle, "1\n00:00:00,000 --> 00:00:01,000\nhello\n").unwrap();
+    let clip = dir.join("clip.ppm");
+    write_ppm(&clip, (2, 2), [255, 255, 255]);
+
+    // playlist items are played by MRL, not by path
+    let mrl = format!("file://{}", clip.display());
+    video
+        .play_media_with_options(&mrl, &[":image-duration=1"])
+        .unwrap();
+    assert!(video.sidecar_errors().is_empty());
+    let media = video.media().unwrap().clone();
+    let expected = format!("file://{}", subtitle.display());
+    assert_eq!(media.slaves(), [expected.as_str()]);
+
+    // playing the same media again does not attach the file twice
+    video.play_with_options(&media, &[] as &[&str]).unwrap();
+    assert_eq!(media.slaves(), [expected.as_str()]);
+    video.stop();
+    fs::remove_dir_all(&dir).unwrap();
+}
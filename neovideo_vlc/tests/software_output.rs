@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use neovideo_vlc::swvideo::Chroma;
+use neovideo_vlc::vlc::{AudioChannel, TrackType};
+use neovideo_vlc::vlcvideo::{VLCVideo, VideoFrame};
+use neovideo_vlc::NeovideoError;
+
+mod common;
+
+use common::{fixture_path, or_skip, write_ppm};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+/// A software output without audio, `None` when libvlc is missing.
+fn software_video(chroma: Chroma) -> Option<VLCVideo> {
+    or_skip(VLCVideo::new_software(chroma, &["--no-audio"]))
+}
+
+/// A `WIDTH` by `HEIGHT` picture of one colour.
+fn solid_ppm(name: &str, rgb: [u8; 3]) -> PathBuf {
+    let path = fixture_path(&format!("{}.ppm", name));
+    write_ppm(&path, (WIDTH, HEIGHT), rgb);
+    path
+}
+
+/// Plays `path` and returns the first decoded frame as RGBA.
+fn first_frame(video: &mut VLCVideo, path: &Path) -> Vec<u8> {
+    video
+        .play_media_with_options(path, &[":image-duration=10"])
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let mut update = false;
+        if let Some(VideoFrame::Cpu(frame)) = video.get_video_frame(&mut update) {
+            assert_eq!((frame.width, frame.height), (WIDTH, HEIGHT));
+            return frame.to_rgba();
+        }
+        assert!(Instant::now() < deadline, "no frame arrived");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn assert_pixels(rgba: &[u8], expected: [u8; 4], tolerance: u8) {
+    assert_eq!(rgba.len(), (WIDTH * HEIGHT * 4) as usize);
+    for pixel in rgba.chunks_exact(4) {
+        for (c, e) in pixel.iter().zip(expected) {
+            assert!(c.abs_diff(e) <= tolerance, "{:?} != {:?}", pixel, expected);
+        }
+    }
+}
+
+#[test]
+fn decodes_rgba() {
+    let mut video = match software_video(Chroma::Rgba) {
+        Some(video) => video,
+        None => return,
+    };
+    let path = solid_ppm("rgba", [255, 128, 0]);
+    let rgba = first_frame(&mut video, &path);
+    assert_pixels(&rgba, [255, 128, 0, 255], 2);
+    assert_eq!(video.video_size(), Some((WIDTH, HEIGHT)));
+    drop(video);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn decodes_i420() {
+    let mut video = match software_video(Chroma::I420) {
+        Some(video) => video,
+        None => return,
+    };
+    let path = solid_ppm("i420", [0, 0, 255]);
+    let rgba = first_frame(&mut video, &path);
+    // studio range YUV loses a little on the way
+    assert_pixels(&rgba, [0, 0, 255, 255], 8);
+    drop(video);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn stopping_drops_the_frame() {
+    let mut video = match software_video(Chroma::Rgba) {
+        Some(video) => video,
+        None => return,
+    };
+    let path = solid_ppm("stop", [0, 255, 0]);
+    first_frame(&mut video, &path);
+    video.stop();
+    let mut update = false;
+    assert!(video.get_video_frame(&mut update).is_none());
+    assert!(update);
+    assert_eq!(video.video_size(), None);
+
+    // the next media gets frames again
+    let rgba = first_frame(&mut video, &path);
+    assert_pixels(&rgba, [0, 255, 0, 255], 2);
+    drop(video);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn player_settings_need_a_media() {
+    let mut video = match software_video(Chroma::Rgba) {
+        Some(video) => video,
+        None => return,
+    };
+    let nothing = Err(NeovideoError::NothingPlaying);
+    assert_eq!(video.set_rate(2.0), nothing);
+    assert_eq!(video.set_volume(50), nothing);
+    assert_eq!(video.set_audio_delay(100), nothing);
+    assert_eq!(video.set_channel(AudioChannel::Stereo), nothing);
+    assert_eq!(video.set_track(TrackType::Text, None), nothing);
+    assert_eq!(video.set_subtitle_delay(100), nothing);
+    // kept for the media played later
+    assert_eq!(video.set_equalizer(None), Ok(()));
+}
+
+#[test]
+fn player_clones_keep_the_output_alive() {
+    let mut video = match software_video(Chroma::Rgba) {
+        Some(video) => video,
+        None => return,
+    };
+    let path = solid_ppm("clone", [255, 255, 255]);
+    first_frame(&mut video, &path);
+    let player = video.media_player().unwrap().clone();
+    let media = video.media().unwrap().clone();
+    drop(video);
+
+    // the callbacks of the player still have a decoder to write into
+    player.set_media(Some(&media));
+    player.play().unwrap();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !player.is_playing() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    thread::sleep(Duration::from_millis(100));
+    player.stop();
+    drop(player);
+    std::fs::remove_file(&path).unwrap();
+}
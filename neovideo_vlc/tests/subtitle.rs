@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use neovideo_vlc::subtitle::{CueAlign, CueLine, Span, SubtitleFormat};
+use neovideo_vlc::{NeovideoError, Subtitles};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn span(text: &str) -> Span {
+    Span {
+        text: text.to_owned(),
+        ..Span::default()
+    }
+}
+
+/// Start times of the cues active at `time`.
+fn active_starts(subtitles: &Subtitles, time: i64) -> Vec<i64> {
+    subtitles.active(time).iter().map(|cue| cue.start).collect()
+}
+
+#[test]
+fn detects_format() {
+    assert_eq!(
+        SubtitleFormat::from_path("movie.en.SRT"),
+        Some(SubtitleFormat::Srt)
+    );
+    assert_eq!(
+        SubtitleFormat::from_path("movie.vtt"),
+        Some(SubtitleFormat::WebVtt)
+    );
+    assert_eq!(SubtitleFormat::from_path("movie.ass"), None);
+    assert_eq!(
+        SubtitleFormat::sniff("\u{feff}WEBVTT\n\n"),
+        SubtitleFormat::WebVtt
+    );
+    assert_eq!(
+        SubtitleFormat::sniff("1\n00:00:01,000"),
+        SubtitleFormat::Srt
+    );
+}
+
+#[test]
+fn both_fixtures_have_the_same_timeline() {
+    for name in ["sample.srt", "sample.vtt"] {
+        let subtitles = Subtitles::load(fixture(name)).unwrap();
+        let cues = subtitles.cues();
+        let timing: Vec<(i64, i64)> = cues.iter().map(|cue| (cue.start, cue.end)).collect();
+        assert_eq!(
+            timing,
+            [(1_000, 3_500), (3_000, 5_000), (60_250, 62_000)],
+            "{}",
+            name
+        );
+        let texts: Vec<String> = cues.iter().map(|cue| cue.text()).collect();
+        assert_eq!(
+            texts,
+            [
+                "Hello, world!",
+                "Two lines,\nthe second in bold",
+                "Tom & Jerry"
+            ],
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn parses_styling_tags() {
+    for name in ["sample.srt", "sample.vtt"] {
+        let subtitles = Subtitles::load(fixture(name)).unwrap();
+        let cues = subtitles.cues();
+        assert_eq!(
+            cues[0].lines,
+            [vec![
+                span("Hello, "),
+                Span {
+                    italic: true,
+                    ..span("world")
+                },
+                span("!"),
+            ]],
+            "{}",
+            name
+        );
+
+        let yellow = Some([255, 255, 0]);
+        assert_eq!(
+            cues[1].lines,
+            [
+                vec![Span {
+                    color: yellow,
+                    ..span("Two lines,")
+                }],
+                vec![
+                    Span {
+                        color: yellow,
+                        ..span("the ")
+                    },
+                    Span {
+                        bold: true,
+                        color: yellow,
+                        ..span("second")
+                    },
+                    Span {
+                        color: yellow,
+                        ..span(" in bold")
+                    },
+                ],
+            ],
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn parses_positioning() {
+    let srt = Subtitles::load(fixture("sample.srt")).unwrap();
+    assert_eq!(srt.cues()[0].settings, Default::default());
+    // {\an8} is top centre
+    assert_eq!(srt.cues()[1].settings.line, Some(CueLine::Percent(0.0)));
+    assert_eq!(srt.cues()[1].settings.align, CueAlign::Center);
+
+    let vtt = Subtitles::load(fixture("sample.vtt")).unwrap();
+    let settings = vtt.cues()[1].settings;
+    assert_eq!(settings.line, Some(CueLine::Percent(10.0)));
+    assert_eq!(settings.align, CueAlign::Start);
+    assert_eq!(settings.position, Some(20.0));
+    assert_eq!(settings.size, Some(60.0));
+    assert_eq!(vtt.cues()[2].settings.line, Some(CueLine::Number(-1)));
+}
+
+#[test]
+fn finds_active_cues() {
+    let subtitles = Subtitles::load(fixture("sample.srt")).unwrap();
+    assert_eq!(active_starts(&subtitles, 0), Vec::<i64>::new());
+    assert_eq!(active_starts(&subtitles, 999), Vec::<i64>::new());
+    assert_eq!(active_starts(&subtitles, 1_000), [1_000]);
+    // overlapping cues are both shown, in start order
+    assert_eq!(active_starts(&subtitles, 3_200), [1_000, 3_000]);
+    // the end time is exclusive
+    assert_eq!(active_starts(&subtitles, 3_500), [3_000]);
+    assert_eq!(active_starts(&subtitles, 5_000), Vec::<i64>::new());
+    assert_eq!(active_starts(&subtitles, 61_999), [60_250]);
+    assert_eq!(active_starts(&subtitles, 62_000), Vec::<i64>::new());
+}
+
+#[test]
+fn accepts_loose_srt() {
+    // no counters, CRLF line endings, short fractions and a missing hour field
+    let srt =
+        "00:00:01,5 --> 00:00:02.25\r\nfirst\r\n\r\n\r\n01:02,000 --> 01:03,000\r\nsecond\r\n";
+    let subtitles = Subtitles::parse(SubtitleFormat::Srt, srt).unwrap();
+    let timing: Vec<(i64, i64)> = subtitles
+        .cues()
+        .iter()
+        .map(|cue| (cue.start, cue.end))
+        .collect();
+    assert_eq!(timing, [(1_500, 2_250), (62_000, 63_000)]);
+    assert_eq!(subtitles.cues()[1].text(), "second");
+}
+
+#[test]
+fn reports_malformed_files() {
+    let srt = "1\n00:00:01,000 --> 00:00:02,000\nfine\n\n2\n00:00:03,000 --> soon\nbroken\n";
+    assert!(matches!(
+        Subtitles::parse(SubtitleFormat::Srt, srt),
+        Err(NeovideoError::SubtitleParse { line: 6, .. })
+    ));
+
+    let backwards = "1\n00:00:05,000 --> 00:00:02,000\nbackwards\n";
+    assert!(matches!(
+        Subtitles::parse(SubtitleFormat::Srt, backwards),
+        Err(NeovideoError::SubtitleParse { line: 2, .. })
+    ));
+
+    let no_header = "00:01.000 --> 00:02.000\ntext\n";
+    assert!(matches!(
+        Subtitles::parse(SubtitleFormat::WebVtt, no_header),
+        Err(NeovideoError::SubtitleParse { line: 1, .. })
+    ));
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use neovideo_vlc::vlc::State;
+use neovideo_vlc::vlcvideo::VideoFrame;
+use neovideo_vlc::{TestPatternSource, VideoSource};
+
+fn frame_pixels(source: &mut TestPatternSource) -> Vec<u8> {
+    let mut update = false;
+    match source.get_video_frame(&mut update) {
+        Some(VideoFrame::Cpu(frame)) => frame.to_rgba(),
+        _ => panic!("the test pattern produces CPU frames"),
+    }
+}
+
+#[test]
+fn frames_depend_only_on_the_index() {
+    let mut source = TestPatternSource::new(64, 48, 25.0).with_length(10_000);
+    source.pause();
+    source.seek_time(1_000);
+    assert_eq!(source.frame_index(), 25);
+    let first = frame_pixels(&mut source);
+
+    source.seek_time(5_000);
+    assert_ne!(frame_pixels(&mut source), first);
+
+    source.seek_time(1_000);
+    assert_eq!(frame_pixels(&mut source), first);
+}
+
+#[test]
+fn draws_bars_and_counter() {
+    let mut source = TestPatternSource::new(70, 60, 10.0);
+    source.pause();
+    source.seek_time(0);
+    let pixels = frame_pixels(&mut source);
+    let pixel = |x: usize, y: usize| &pixels[(y * 70 + x) * 4..(y * 70 + x) * 4 + 4];
+
+    assert_eq!(pixel(0, 0), [191, 191, 191, 255]);
+    assert_eq!(pixel(69, 0), [0, 0, 191, 255]);
+    assert_eq!(pixel(69, 59), [0, 0, 0, 255]);
+    // top left cell of the "0" glyph under the bars
+    assert_eq!(pixel(4, 44), [255, 255, 255, 255]);
+}
+
+#[test]
+fn next_frame_steps_one_frame_and_pauses() {
+    let mut source = TestPatternSource::new(16, 16, 30.0);
+    source.seek_time(0);
+    source.next_frame();
+    assert_eq!(source.state(), State::Paused);
+    assert_eq!(source.frame_index(), 1);
+    for _ in 0..29 {
+        source.next_frame();
+    }
+    assert_eq!(source.frame_index(), 30);
+    assert_eq!(source.time(), Some(1_000));
+}
+
+#[test]
+fn ends_after_its_length() {
+    let mut source = TestPatternSource::new(16, 16, 25.0).with_length(2_000);
+    source.seek_position(1.0);
+    assert_eq!(source.state(), State::Ended);
+    assert_eq!(source.position(), Some(1.0));
+    assert!(source.set_rate(0.0).is_err());
+}
+
+#[test]
+fn next_frame_is_due_within_one_frame_while_playing() {
+    let mut source = TestPatternSource::new(16, 16, 10.0);
+    let wait = source.next_frame_in().unwrap();
+    assert!(wait <= Duration::from_millis(100));
+    source.pause();
+    assert_eq!(source.next_frame_in(), None);
+}
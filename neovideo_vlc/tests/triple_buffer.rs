@@ -0,0 +1,87 @@
+use std::cell::Cell;
+use std::thread;
+
+use neovideo_vlc::triple_buffer::{triple_buffer, Reader, Writer};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn reader_sees_only_published_frames() {
+    let (mut writer, mut reader) = triple_buffer([0, 0, 0]);
+    assert!(!reader.has_update());
+    assert!(!reader.update());
+
+    *writer.buffer() = 1;
+    assert!(!reader.update());
+    writer.publish();
+    assert!(reader.has_update());
+    assert!(reader.update());
+    assert_eq!(*reader.buffer(), 1);
+    // the held frame stays until a newer one is published
+    assert!(!reader.update());
+    assert_eq!(*reader.buffer(), 1);
+}
+
+#[test]
+fn unread_frames_are_replaced() {
+    let (mut writer, mut reader) = triple_buffer([0, 0, 0]);
+    for frame in 1..=5 {
+        *writer.buffer() = frame;
+        writer.publish();
+    }
+    assert!(reader.update());
+    assert_eq!(*reader.buffer(), 5);
+    assert!(!reader.update());
+}
+
+#[test]
+fn writer_never_gets_the_held_buffer() {
+    let (mut writer, mut reader) = triple_buffer([0, 0, 0]);
+    *writer.buffer() = 1;
+    writer.publish();
+    reader.update();
+    for frame in 2..10 {
+        *writer.buffer() = frame;
+        writer.publish();
+        assert_eq!(*reader.buffer(), 1);
+    }
+    let [_, _, held] = writer.buffers_mut(&mut reader);
+    assert_eq!(*held, 1);
+}
+
+#[test]
+fn frames_cross_threads_whole_and_in_order() {
+    const FRAMES: u64 = 20_000;
+    let (mut writer, mut reader) = triple_buffer([[0u64; 64], [0; 64], [0; 64]]);
+    let producer = thread::spawn(move || {
+        for frame in 1..=FRAMES {
+            for value in writer.buffer().iter_mut() {
+                *value = frame;
+            }
+            writer.publish();
+        }
+    });
+    let mut last = 0;
+    while last < FRAMES {
+        if reader.update() {
+            let buffer = reader.buffer();
+            // a torn frame would mix values of two frames
+            assert!(buffer.iter().all(|value| *value == buffer[0]));
+            assert!(buffer[0] > last);
+            last = buffer[0];
+        } else {
+            thread::yield_now();
+        }
+    }
+    producer.join().unwrap();
+}
+
+#[test]
+fn halves_move_between_threads() {
+    // `Cell` may move to another thread but not be shared, `Reader<Cell<_>>` not being
+    // `Sync` is checked by a compile_fail doctest
+    assert_send::<Writer<Cell<u8>>>();
+    assert_send::<Reader<Cell<u8>>>();
+    assert_sync::<Reader<u8>>();
+}